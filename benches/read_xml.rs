@@ -0,0 +1,87 @@
+//! `read_xml`・`read_xml_ref`の読み込み速度を、XMLの解析のみ(`quick_xml`のイベントを読み捨てる)と比較します。
+//!
+//! `cargo bench --bench read_xml`で実行します。
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use pick_up_xml_value::reader::{
+    pick_up::build_read_config, xml_read_config::TagValueName, xml_reader::read_xml,
+    xml_ref_reader::read_xml_ref,
+};
+use quick_xml::events::Event;
+
+/// `test_xml/sample1.xml`の`programme`タグを繰り返した、大きなXMLを作成します。
+fn build_large_xml(programme_count: usize) -> String {
+    let sample = std::fs::read_to_string("test_xml/sample1.xml").unwrap();
+    let start = sample.find("  <programme ").unwrap();
+    let end = sample.find("</programme>").unwrap() + "</programme>\n".len();
+    let mut xml = sample[..start].to_string();
+    for _ in 0..programme_count {
+        xml.push_str(&sample[start..end]);
+    }
+    xml.push_str("</tv>\n");
+    xml
+}
+
+fn bench_read_xml(c: &mut Criterion) {
+    let xml = build_large_xml(5_000);
+    let text = TagValueName::new_tag_text;
+    let attribute = |name: &str| TagValueName::new_attribute(name.to_string());
+    let read_config = build_read_config(
+        "tv/programme",
+        vec![
+            ("", attribute("start")),
+            ("", attribute("stop")),
+            ("", attribute("channel")),
+            ("title", text()),
+            ("desc", text()),
+            ("category", text()),
+            ("category", attribute("lang")),
+            ("extdesc/item_description", text()),
+            ("extdesc/item", text()),
+        ],
+    );
+
+    let mut group = c.benchmark_group("read_xml");
+    group.throughput(Throughput::Bytes(xml.len() as u64));
+    group.bench_function("quick_xml_events", |b| {
+        b.iter(|| {
+            let mut reader = quick_xml::Reader::from_reader(xml.as_bytes());
+            let mut buf = Vec::new();
+            let mut count = 0usize;
+            loop {
+                match reader.read_event_into(&mut buf).unwrap() {
+                    Event::Eof => break,
+                    event => {
+                        black_box(event);
+                        count += 1;
+                    }
+                }
+                buf.clear();
+            }
+            count
+        })
+    });
+    group.bench_function("read_xml", |b| {
+        b.iter(|| {
+            let mut reader = quick_xml::Reader::from_reader(xml.as_bytes());
+            black_box(read_xml(&mut reader, &read_config).unwrap())
+        })
+    });
+    group.bench_function("read_xml_ref", |b| {
+        b.iter(|| black_box(read_xml_ref(&xml, &read_config).unwrap()))
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_function("read_xml_parallel", |b| {
+        b.iter(|| {
+            black_box(
+                pick_up_xml_value::reader::parallel::read_xml_parallel(&xml, &read_config).unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_xml);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use pick_up_xml_value::reader::{
+    discover::{DEFAULT_SAMPLE_LIMIT, DiscoveryReport, discover_with_sample_limit},
+    util::parse_tag_path,
+};
+
+use crate::open_input;
+use crate::table::write_rows;
+
+/// 文書に含まれるタグ階層と属性を一覧表示します。
+#[derive(Debug, Args)]
+pub struct DiscoverArgs {
+    /// 入力ファイル(省略または`-`の場合は標準入力)
+    pub file: Option<PathBuf>,
+
+    /// タグ階層・属性ごとに表示する値の例の数
+    #[arg(long, default_value_t = DEFAULT_SAMPLE_LIMIT)]
+    pub samples: usize,
+
+    /// 一覧の代わりに、見つかったすべての値を取得する設定(TOML)を出力する
+    #[arg(long)]
+    pub starter_config: bool,
+
+    /// `--starter-config`で使用するグループのタグのパス(省略時は推測する)
+    #[arg(short, long)]
+    pub group: Option<String>,
+}
+
+/// タグ階層ごとの出現回数を表示します。
+#[derive(Debug, Args)]
+pub struct CountArgs {
+    /// 入力ファイル(省略または`-`の場合は標準入力)
+    pub file: Option<PathBuf>,
+}
+
+fn read_report(file: &Option<PathBuf>, samples: usize) -> Result<DiscoveryReport, Box<dyn Error>> {
+    let mut reader = quick_xml::Reader::from_reader(open_input(file)?);
+    discover_with_sample_limit(&mut reader, samples)
+}
+
+fn write_report<W: Write>(writer: &mut W, report: &DiscoveryReport) -> Result<(), Box<dyn Error>> {
+    let mut rows = vec![vec![
+        "hierarchy".to_string(),
+        "count".to_string(),
+        "samples".to_string(),
+    ]];
+    for summary in report.get_hierarchies() {
+        let tag_hierarchy = summary.get_tag_hierarchy().join("/");
+        rows.push(vec![
+            tag_hierarchy.clone(),
+            summary.get_count().to_string(),
+            String::new(),
+        ]);
+        if summary.has_text() {
+            rows.push(vec![
+                format!("{}/text()", tag_hierarchy),
+                summary.get_text_count().to_string(),
+                summary.get_text_samples().join(" | "),
+            ]);
+        }
+        for attribute in summary.get_attributes() {
+            rows.push(vec![
+                format!("{}/@{}", tag_hierarchy, attribute.get_name()),
+                attribute.get_count().to_string(),
+                attribute.get_samples().join(" | "),
+            ]);
+        }
+    }
+    write_rows(writer, &rows)
+}
+
+fn write_counts<W: Write>(writer: &mut W, report: &DiscoveryReport) -> Result<(), Box<dyn Error>> {
+    let mut rows = vec![vec!["count".to_string(), "hierarchy".to_string()]];
+    for summary in report.get_hierarchies() {
+        rows.push(vec![
+            summary.get_count().to_string(),
+            summary.get_tag_hierarchy().join("/"),
+        ]);
+    }
+    write_rows(writer, &rows)
+}
+
+pub fn run_discover(args: DiscoverArgs) -> Result<(), Box<dyn Error>> {
+    let report = read_report(&args.file, args.samples)?;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if args.starter_config {
+        let tag_group_hierarchy = match &args.group {
+            Some(group) => parse_tag_path(group),
+            None => report
+                .suggest_tag_group_hierarchy()
+                .ok_or("NO ELEMENTS FOUND IN DOCUMENT.")?,
+        };
+        let config = report.to_read_config(tag_group_hierarchy);
+        write!(out, "{}", config.to_toml_string()?)?;
+        out.flush()?;
+        Ok(())
+    } else {
+        write_report(&mut out, &report)
+    }
+}
+
+pub fn run_count(args: CountArgs) -> Result<(), Box<dyn Error>> {
+    let report = read_report(&args.file, 0)?;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    write_counts(&mut out, &report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML_FILE: &str = "../test_xml/sample1.xml";
+
+    #[test]
+    fn test_write_counts() {
+        let report = read_report(&Some(PathBuf::from(XML_FILE)), 0).unwrap();
+        let mut out = Vec::new();
+        write_counts(&mut out, &report).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "count  hierarchy");
+        assert_eq!(lines[1], "1      tv");
+        assert_eq!(lines[2], "2      tv/channel");
+        assert!(lines.contains(&"4      tv/programme/category"));
+    }
+}
@@ -0,0 +1,233 @@
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use pick_up_xml_value::reader::{
+    csv_writer::{CsvColumn, CsvWriter, write_xml_as_csv},
+    json_writer::{write_xml_as_json, write_xml_as_ndjson},
+    pick_up::build_read_config,
+    util::parse_tag_path,
+    xml_read_config::{TagValueName, XmlReadConfig},
+    xml_reader::read_xml_each,
+};
+
+use crate::open_input;
+use crate::table::write_rows;
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 列を揃えた表
+    Table,
+    /// カンマ区切り
+    Csv,
+    /// タブ区切り
+    Tsv,
+    /// グループの配列
+    Json,
+    /// 1グループ1行のJSON
+    Ndjson,
+}
+
+/// グループごとに値を取り出して表示します。
+#[derive(Debug, Args)]
+pub struct ExtractArgs {
+    /// 入力ファイル(省略または`-`の場合は標準入力)
+    pub file: Option<PathBuf>,
+
+    /// グループ化の単位となるタグのパス(例: tv/programme)
+    #[arg(short, long, required = true)]
+    pub group: Option<String>,
+
+    /// 取り出す値(グループからの相対パス。例: @start, title, title/@lang, text())
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    pub select: Vec<String>,
+
+    /// 出力形式
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+
+    /// 1つの値に複数の値がある場合の連結文字列(table/csv/tsv)
+    #[arg(long, default_value = "|")]
+    pub separator: String,
+}
+
+/// 取り出す値の指定
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Selector {
+    /// 元の文字列(見出しに使用する)
+    source: String,
+    /// グループからの相対パス
+    path: String,
+    /// 取得する項目
+    tag_value_name: TagValueName,
+}
+
+impl Selector {
+    /// `title`、`title/@lang`、`@start`、`text()`の形式の文字列を解析します。
+    fn parse(source: &str) -> Result<Self, Box<dyn Error>> {
+        let source = source.trim();
+        let mut path = parse_tag_path(source);
+        let tag_value_name = match path.last().map(|x| x.as_str()) {
+            Some("text()") => {
+                path.pop();
+                TagValueName::new_tag_text()
+            }
+            Some(last) if last.starts_with('@') => {
+                let tag_value_name: TagValueName = last.parse()?;
+                path.pop();
+                tag_value_name
+            }
+            Some(_) => TagValueName::new_tag_text(),
+            None => return Err(format!("EMPTY SELECTOR. {:?}", source).into()),
+        };
+        Ok(Selector {
+            source: source.to_string(),
+            path: path.join("/"),
+            tag_value_name,
+        })
+    }
+}
+
+fn write_table<R: BufRead, W: Write>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    columns: Vec<CsvColumn>,
+    separator: &str,
+    writer: &mut W,
+) -> Result<usize, Box<dyn Error>> {
+    // 列幅を揃えるため、いったんすべての行を作成する
+    let headers: Vec<String> = columns.iter().map(|x| x.get_header().to_string()).collect();
+    let mut rows: Vec<Vec<String>> = vec![headers];
+    read_xml_each(reader, read_config, |group| {
+        rows.push(
+            columns
+                .iter()
+                .map(|column| column.pick_up_values(&group).join(separator))
+                .collect(),
+        );
+        Ok(())
+    })?;
+    write_rows(writer, &rows)?;
+    Ok(rows.len() - 1)
+}
+
+pub fn run(args: ExtractArgs) -> Result<(), Box<dyn Error>> {
+    let selectors = args
+        .select
+        .iter()
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| Selector::parse(x))
+        .collect::<Result<Vec<_>, _>>()?;
+    let read_config = build_read_config(
+        args.group.as_deref().unwrap_or_default(),
+        selectors
+            .iter()
+            .map(|x| (x.path.as_str(), x.tag_value_name.clone()))
+            .collect(),
+    );
+    let tag_group_hierarchy = read_config.get_tag_group_hierarchy();
+    let columns: Vec<CsvColumn> = selectors
+        .iter()
+        .map(|x| {
+            let mut tag_hierarchy = tag_group_hierarchy.clone();
+            tag_hierarchy.extend(parse_tag_path(&x.path));
+            CsvColumn::new(x.source.clone(), tag_hierarchy, x.tag_value_name.clone())
+        })
+        .collect();
+
+    let mut reader = quick_xml::Reader::from_reader(open_input(&args.file)?);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    match args.format {
+        OutputFormat::Table => {
+            write_table(
+                &mut reader,
+                &read_config,
+                columns,
+                &args.separator,
+                &mut out,
+            )?;
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let mut csv_writer = if args.format == OutputFormat::Csv {
+                CsvWriter::new(&mut out, columns)
+            } else {
+                CsvWriter::new_tsv(&mut out, columns)
+            };
+            csv_writer.set_multi_value_separator(&args.separator);
+            write_xml_as_csv(&mut reader, &read_config, &mut csv_writer)?;
+        }
+        OutputFormat::Json => {
+            write_xml_as_json(&mut reader, &read_config, &mut out)?;
+        }
+        OutputFormat::Ndjson => {
+            write_xml_as_ndjson(&mut reader, &read_config, &mut out)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use super::*;
+
+    const XML_FILE: &str = "../test_xml/sample1.xml";
+
+    #[test]
+    fn test_selector_parse() {
+        let selector = Selector::parse("title/@lang").unwrap();
+        assert_eq!(selector.path, "title");
+        assert_eq!(
+            selector.tag_value_name,
+            TagValueName::new_attribute("lang".to_string())
+        );
+        let selector = Selector::parse("@start").unwrap();
+        assert_eq!(selector.path, "");
+        let selector = Selector::parse("video/aspect").unwrap();
+        assert_eq!(selector.path, "video/aspect");
+        assert_eq!(selector.tag_value_name, TagValueName::new_tag_text());
+        let selector = Selector::parse("text()").unwrap();
+        assert_eq!(selector.path, "");
+        assert_eq!(selector.tag_value_name, TagValueName::new_tag_text());
+        assert!(Selector::parse("title/@").is_err());
+        assert!(Selector::parse("/").is_err());
+    }
+
+    #[test]
+    fn test_write_table() {
+        let read_config = build_read_config(
+            "tv/channel",
+            vec![
+                ("", TagValueName::new_attribute("id".to_string())),
+                ("display-name", TagValueName::new_tag_text()),
+            ],
+        );
+        let columns = vec![
+            CsvColumn::new(
+                "@id".to_string(),
+                parse_tag_path("tv/channel"),
+                TagValueName::new_attribute("id".to_string()),
+            ),
+            CsvColumn::new(
+                "display-name".to_string(),
+                parse_tag_path("tv/channel/display-name"),
+                TagValueName::new_tag_text(),
+            ),
+        ];
+        let mut reader =
+            quick_xml::Reader::from_reader(BufReader::new(File::open(XML_FILE).unwrap()));
+        let mut out = Vec::new();
+        let count = write_table(&mut reader, &read_config, columns, "|", &mut out).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "@id       display-name\n\
+             GR2_1032  CHANNEL_NAME_1_1\n\
+             GR2_1034  CHANNEL_NAME_1_2\n"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! `read_xml`を使ってXMLから値を取り出すコマンドです。
+//!
+//! ```text
+//! pick-up-xml --group tv/programme --select @start,@channel,title epg.xml
+//! cat epg.xml | pick-up-xml -g tv/channel -s @id -s display-name -f csv
+//! pick-up-xml discover epg.xml
+//! pick-up-xml validate --config programme.toml epg.xml
+//! pick-up-xml count epg.xml
+//! ```
+
+mod discover;
+mod extract;
+mod table;
+mod validate;
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use pick_up_xml_value::reader::compression::decode_xml_input;
+
+/// XMLからグループごとに値を取り出して表示します。
+#[derive(Debug, Parser)]
+#[command(
+    name = "pick-up-xml",
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    extract: extract::ExtractArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// グループごとに値を取り出して表示します。(サブコマンドを省略した場合と同じ)
+    Extract(extract::ExtractArgs),
+    /// 文書に含まれるタグ階層と属性を一覧表示します。
+    Discover(discover::DiscoverArgs),
+    /// 設定ファイルを文書またはDTD・XSDと照らし合わせて検証します。
+    Validate(validate::ValidateArgs),
+    /// タグ階層ごとの出現回数を表示します。
+    Count(discover::CountArgs),
+}
+
+/// 入力ファイルを開きます。(`None`または`-`の場合は標準入力、圧縮されたファイルは展開する)
+fn open_input(file: &Option<PathBuf>) -> Result<Box<dyn BufRead>, Box<dyn Error>> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let reader = decode_xml_input(BufReader::new(file))
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            Ok(reader)
+        }
+        _ => Ok(Box::new(BufReader::new(io::stdin().lock()))),
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        None => extract::run(cli.extract),
+        Some(Command::Extract(args)) => extract::run(args),
+        Some(Command::Discover(args)) => discover::run_discover(args),
+        Some(Command::Validate(args)) => validate::run(args),
+        Some(Command::Count(args)) => discover::run_count(args),
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("pick-up-xml: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["pick-up-xml", "-g", "tv/channel", "-s", "@id"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.extract.group.as_deref(), Some("tv/channel"));
+
+        let cli = Cli::try_parse_from(["pick-up-xml", "count", "epg.xml"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Count(_))));
+
+        assert!(Cli::try_parse_from(["pick-up-xml", "epg.xml"]).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use std::error::Error;
+use std::io::Write;
+
+use unicode_width::UnicodeWidthStr;
+
+/// 列幅を揃えて行を書き出します。(最初の行は見出し)
+pub fn write_rows<W: Write>(writer: &mut W, rows: &[Vec<String>]) -> Result<(), Box<dyn Error>> {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+    let mut widths = vec![0; columns];
+    for row in rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.width());
+        }
+    }
+    for row in rows {
+        let mut line = String::new();
+        for (i, (width, field)) in widths.iter().zip(row).enumerate() {
+            if i > 0 {
+                line.push_str("  ");
+            }
+            line.push_str(field);
+            if i + 1 < row.len() {
+                line.push_str(&" ".repeat(width - field.width()));
+            }
+        }
+        writeln!(writer, "{}", line)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
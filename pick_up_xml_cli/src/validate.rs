@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::Args;
+use pick_up_xml_value::reader::{
+    validator::{validate_with_dtd, validate_with_sample, validate_with_xsd},
+    xml_read_config::XmlReadConfig,
+};
+
+use crate::open_input;
+
+/// 設定ファイル(TOML/JSON)を文書またはDTD・XSDと照らし合わせて検証します。
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// 検証する設定ファイル(.toml/.json)
+    #[arg(short, long)]
+    pub config: PathBuf,
+
+    /// 文書の代わりにDTDで検証する
+    #[arg(long, conflicts_with = "file")]
+    pub dtd: Option<PathBuf>,
+
+    /// 文書の代わりにXSD(XMLスキーマ)で検証する
+    #[arg(long, conflicts_with_all = ["file", "dtd"])]
+    pub xsd: Option<PathBuf>,
+
+    /// 検証に使用する文書(省略または`-`の場合は標準入力)
+    pub file: Option<PathBuf>,
+}
+
+pub fn run(args: ValidateArgs) -> Result<(), Box<dyn Error>> {
+    let config = XmlReadConfig::from_file(&args.config)?;
+    let read_to_string = |path: &PathBuf| {
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    let report = match (&args.dtd, &args.xsd) {
+        (Some(dtd), _) => validate_with_dtd(&read_to_string(dtd)?, &config)?,
+        (None, Some(xsd)) => validate_with_xsd(&read_to_string(xsd)?, &config)?,
+        (None, None) => {
+            let mut reader = quick_xml::Reader::from_reader(open_input(&args.file)?);
+            validate_with_sample(&mut reader, &config)?
+        }
+    };
+    let mut out = io::stdout().lock();
+    write!(out, "{}", report)?;
+    out.flush()?;
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(format!("{} issue(s) found", report.get_issues().len()).into())
+    }
+}
@@ -0,0 +1,220 @@
+//! `pick_up_xml_value`の`PickUp`トレイトを実装する derive マクロです。
+//!
+//! ```ignore
+//! #[derive(PickUp)]
+//! #[pick(group = "tv/programme")]
+//! struct Programme {
+//!     #[pick(attr = "start")]
+//!     start: String,
+//!     #[pick(path = "title", text)]
+//!     title: Vec<String>,
+//!     #[pick(path = "title", attr = "lang")]
+//!     title_lang: Option<String>,
+//! }
+//! ```
+//!
+//! - 構造体の`#[pick(group = "...")]`で、グループ化の単位となるタグのパスを指定します。
+//! - フィールドの`#[pick(...)]`で、グループからの相対パス(`path`、省略時はグループのタグ自身)と、
+//!   取得する項目(`text`または`attr = "属性名"`)を指定します。
+//! - フィールドの型が`Option<T>`であれば0個または1つ、`Vec<T>`であれば任意の個数、
+//!   それ以外であればちょうど1つの値を`FromStr`で変換して設定します。
+//! - `#[pick(skip)]`を指定したフィールドは`Default::default()`で設定します。
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type, parse_macro_input,
+    spanned::Spanned,
+};
+
+#[proc_macro_derive(PickUp, attributes(pick))]
+pub fn derive_pick_up(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// フィールドの値の個数
+enum Cardinality {
+    Single,
+    Option,
+    Vec,
+}
+
+/// フィールドから取得する項目
+enum Target {
+    Text,
+    Attribute(LitStr),
+}
+
+/// フィールドの`#[pick(...)]`の内容
+struct FieldConfig {
+    path: Option<LitStr>,
+    target: Option<Target>,
+    skip: bool,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let group = parse_group(input)?;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "PickUp can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "PickUp can only be derived for structs",
+            ));
+        }
+    };
+
+    let krate = quote!(::pick_up_xml_value::reader);
+    let mut targets = Vec::new();
+    let mut initializers = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let field_name = ident.to_string();
+        let config = parse_field(field)?;
+        if config.skip {
+            initializers.push(quote! { #ident: ::core::default::Default::default() });
+            continue;
+        }
+        let path = config.path.map(|path| path.value()).unwrap_or_default();
+        let tag_value_name = match config.target {
+            Some(Target::Text) => quote! { #krate::xml_read_config::TagValueName::new_tag_text() },
+            Some(Target::Attribute(attr)) => quote! {
+                #krate::xml_read_config::TagValueName::new_attribute(::std::string::String::from(#attr))
+            },
+            None => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "expected `text` or `attr = \"...\"` in #[pick(...)]",
+                ));
+            }
+        };
+        targets.push(quote! { (#path, #tag_value_name) });
+        let convert = match cardinality(&field.ty) {
+            Cardinality::Single => quote!(convert_single),
+            Cardinality::Option => quote!(convert_option),
+            Cardinality::Vec => quote!(convert_vec),
+        };
+        initializers.push(quote! {
+            #ident: #krate::pick_up::#convert(
+                #field_name,
+                #krate::pick_up::pick_up_values(group, #path, &#tag_value_name),
+            )?
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::pick_up::PickUp for #name #ty_generics #where_clause {
+            fn read_config() -> #krate::xml_read_config::XmlReadConfig {
+                #krate::pick_up::build_read_config(#group, ::std::vec![#(#targets),*])
+            }
+
+            fn from_group(
+                group: &[#krate::output_tag_value::OutPutTagValue],
+            ) -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+                ::std::result::Result::Ok(Self {
+                    #(#initializers),*
+                })
+            }
+        }
+    })
+}
+
+fn parse_group(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut group = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pick"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("group") {
+                group = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported #[pick(...)] attribute on struct"))
+            }
+        })?;
+    }
+    group.ok_or_else(|| {
+        syn::Error::new(
+            input.ident.span(),
+            "missing #[pick(group = \"...\")] on struct",
+        )
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<FieldConfig> {
+    let mut config = FieldConfig {
+        path: None,
+        target: None,
+        skip: false,
+    };
+    let mut found = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pick"))
+    {
+        found = true;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("path") {
+                config.path = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("text") {
+                if config.target.is_some() {
+                    return Err(meta.error("`text` and `attr` cannot be used together"));
+                }
+                config.target = Some(Target::Text);
+            } else if meta.path.is_ident("attr") {
+                if config.target.is_some() {
+                    return Err(meta.error("`text` and `attr` cannot be used together"));
+                }
+                config.target = Some(Target::Attribute(meta.value()?.parse::<LitStr>()?));
+            } else if meta.path.is_ident("skip") {
+                config.skip = true;
+            } else {
+                return Err(meta.error("unsupported #[pick(...)] attribute on field"));
+            }
+            Ok(())
+        })?;
+    }
+    if !found {
+        return Err(syn::Error::new(
+            field.span(),
+            "missing #[pick(...)] on field (use #[pick(skip)] to leave it default)",
+        ));
+    }
+    Ok(config)
+}
+
+fn cardinality(ty: &Type) -> Cardinality {
+    if let Type::Path(type_path) = ty
+        && type_path.qself.is_none()
+        && let Some(segment) = type_path.path.segments.last()
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && args.args.len() == 1
+        && matches!(args.args.first(), Some(GenericArgument::Type(_)))
+    {
+        if segment.ident == "Option" {
+            return Cardinality::Option;
+        }
+        if segment.ident == "Vec" {
+            return Cardinality::Vec;
+        }
+    }
+    Cardinality::Single
+}
@@ -0,0 +1,64 @@
+use pick_up_xml_value::reader::{
+    pick_up::PickUp, util::string_vec, xml_read_config::TagValueName, xml_reader::read_xml,
+};
+
+const XML_FILE: &str = "../test_xml/sample1.xml";
+
+#[derive(Debug, PickUp)]
+#[pick(group = "tv/programme")]
+struct Programme {
+    #[pick(attr = "start")]
+    start: String,
+    #[pick(attr = "event_id")]
+    event_id: u32,
+    #[pick(path = "title", text)]
+    title: String,
+    #[pick(path = "title", attr = "lang")]
+    title_lang: Option<String>,
+    #[pick(path = "category", text)]
+    categories: Vec<String>,
+    #[pick(path = "video/aspect", text)]
+    aspect: Option<String>,
+    #[pick(skip)]
+    note: String,
+}
+
+#[test_log::test]
+fn test_derive_read_config() {
+    let config = Programme::read_config();
+    assert_eq!(
+        config.get_tag_group_hierarchy(),
+        string_vec(vec!["tv", "programme"])
+    );
+    let tag_hierarchy_map = config.get_tag_hierarchy_map();
+    assert_eq!(tag_hierarchy_map.len(), 4);
+    let title = &tag_hierarchy_map[&string_vec(vec!["tv", "programme", "title"])];
+    assert!(
+        title
+            .get_target_tag_value_names()
+            .contains(&TagValueName::new_tag_text())
+    );
+    assert!(
+        title
+            .get_target_tag_value_names()
+            .contains(&TagValueName::new_attribute("lang".to_string()))
+    );
+}
+
+#[test_log::test]
+fn test_derive_from_groups() {
+    let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+        std::fs::File::open(XML_FILE).unwrap(),
+    ));
+    let groups = read_xml(&mut reader, &Programme::read_config()).unwrap();
+    let programmes = Programme::from_groups(&groups).unwrap();
+    assert_eq!(programmes.len(), 2);
+    assert_eq!(programmes[0].start, "20241123090000 +0900");
+    assert_eq!(programmes[0].event_id, 32665);
+    assert_eq!(programmes[1].event_id, 32666);
+    assert_eq!(programmes[0].title, "TITLE_1_1");
+    assert_eq!(programmes[0].title_lang.as_deref(), Some("ja_JP"));
+    assert_eq!(programmes[0].categories, vec!["CAT_1", "CAT_2"]);
+    assert_eq!(programmes[0].aspect.as_deref(), Some("16:9"));
+    assert_eq!(programmes[0].note, "");
+}
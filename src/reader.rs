@@ -1,7 +1,32 @@
+pub mod batch;
+pub mod compression;
+#[cfg(feature = "serde")]
+mod config_file;
+pub mod csv_writer;
+pub mod discover;
+pub mod group;
+#[cfg(feature = "serde")]
+pub mod group_deserializer;
 mod hierarchy_tag_store;
+#[cfg(feature = "json")]
+pub mod json_writer;
+pub mod lang;
 pub mod output_tag_value;
+pub mod output_tag_value_ref;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod pick_up;
+mod raw_xml_capture;
+mod read_state;
+mod tag_matcher;
 pub mod tag_value;
+pub mod tag_value_ref;
 pub mod tag_value_type;
+pub mod typed_value;
 pub mod util;
+pub mod validator;
+pub mod xml_document;
 pub mod xml_read_config;
 pub mod xml_reader;
+pub mod xml_ref_reader;
+pub mod xmltv;
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, mpsc};
+use std::thread;
+
+use super::compression::open_xml_file;
+use super::output_tag_value::OutPutTagValue;
+use super::xml_read_config::XmlReadConfig;
+use super::xml_reader::read_xml;
+
+/// 1スレッドあたりの、呼び出し元の処理より先に読み込めるファイルの数
+/// (読み込んだ結果を保持するファイルの数を制限するため)
+const FILES_AHEAD_PER_THREAD: usize = 2;
+
+/// 読み込みに失敗したファイルがあった場合の動作です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchErrorMode {
+    /// 失敗したファイルを`BatchSummary`に記録して、残りのファイルの読み込みを続ける
+    #[default]
+    Continue,
+    /// 読み込みを中止して、そのエラーを返す
+    Abort,
+}
+
+/// 読み込みに失敗したファイルです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchFailure {
+    /// ファイルのパス
+    path: PathBuf,
+    /// エラーメッセージ
+    message: String,
+}
+
+impl BatchFailure {
+    /// ファイルのパスを取得します。
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// エラーメッセージを取得します。
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// 複数のファイルの読み込み結果の集計です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// 読み込んだファイルの数(失敗したファイルを含む)
+    file_count: usize,
+    /// 取得したグループの数(すべてのファイルの合計)
+    group_count: usize,
+    /// 読み込みに失敗したファイル(指定した順)
+    failures: Vec<BatchFailure>,
+}
+
+impl BatchSummary {
+    /// 読み込んだファイルの数(失敗したファイルを含む)を取得します。
+    pub fn get_file_count(&self) -> usize {
+        self.file_count
+    }
+
+    /// 読み込みに成功したファイルの数を取得します。
+    pub fn get_succeeded_file_count(&self) -> usize {
+        self.file_count - self.failures.len()
+    }
+
+    /// 取得したグループの数(すべてのファイルの合計)を取得します。
+    pub fn get_group_count(&self) -> usize {
+        self.group_count
+    }
+
+    /// 読み込みに失敗したファイルを取得します。
+    pub fn get_failures(&self) -> &[BatchFailure] {
+        &self.failures
+    }
+
+    /// すべてのファイルの読み込みに成功したかどうかを確認します。
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// 1つのファイルから取得したグループです。(`read_xml_files`で取得)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlFileGroups {
+    /// ファイルのパス
+    path: PathBuf,
+    /// グループごとの値(`read_xml`の結果と同じ)
+    groups: Vec<Vec<OutPutTagValue>>,
+}
+
+impl XmlFileGroups {
+    /// ファイルのパスを取得します。
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// グループごとの値を取得します。
+    pub fn get_groups(&self) -> &[Vec<OutPutTagValue>] {
+        &self.groups
+    }
+
+    /// ファイルのパスと、グループごとの値に分けます。
+    pub fn into_parts(self) -> (PathBuf, Vec<Vec<OutPutTagValue>>) {
+        (self.path, self.groups)
+    }
+}
+
+/// パターン(`epg/*.xml`など)に一致するファイルのパスを、名前の順に取得します。(`glob`フィーチャーが必要)
+#[cfg(feature = "glob")]
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut paths = Vec::new();
+    for entry in glob::glob(pattern)? {
+        let path = entry?;
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// パターンに一致するファイルを読み込みます。(`glob`フィーチャーが必要、`expand_glob`・`read_xml_files`を参照)
+#[cfg(feature = "glob")]
+pub fn read_xml_glob(
+    pattern: &str,
+    read_config: &XmlReadConfig,
+    error_mode: BatchErrorMode,
+) -> Result<(Vec<XmlFileGroups>, BatchSummary), Box<dyn Error>> {
+    read_xml_files(&expand_glob(pattern)?, read_config, error_mode)
+}
+
+/// 複数のファイルを同じ設定で並行して読み込み、ファイルごとの結果と集計を返します。
+///
+/// 結果は、読み込みに成功したファイルのみ、指定した順に並びます。
+pub fn read_xml_files<P>(
+    paths: &[P],
+    read_config: &XmlReadConfig,
+    error_mode: BatchErrorMode,
+) -> Result<(Vec<XmlFileGroups>, BatchSummary), Box<dyn Error>>
+where
+    P: AsRef<Path> + Sync,
+{
+    let mut files: Vec<XmlFileGroups> = Vec::new();
+    let summary = read_xml_files_each(paths, read_config, error_mode, |path, groups| {
+        files.push(XmlFileGroups {
+            path: path.to_path_buf(),
+            groups,
+        });
+        Ok(())
+    })?;
+    Ok((files, summary))
+}
+
+/// 複数のファイルを同じ設定で並行して読み込み、ファイルを1つ読み込むたびに`on_file`を呼び出します。
+///
+/// ファイルはスレッド(使用できるCPUの数まで)で読み込みますが、`on_file`は呼び出し元のスレッドで、
+/// 指定した順に呼び出します。(読み込みに失敗したファイルでは呼び出さない)
+/// 結果を保持するファイルの数を抑えるため、`on_file`を呼び出したファイルより先は、
+/// スレッドの数の数倍のファイルまでしか読み込みません。
+/// 圧縮されたファイルは`open_xml_file`と同じく展開して読み込みます。
+/// `error_mode`が`Abort`の場合、または`on_file`がエラーを返した場合は、
+/// 読み込み中のファイルの完了を待って中止し、そのエラーを返します。
+///
+/// # 引数
+/// - `paths`: ファイルのパスのリスト
+/// - `read_config`: 読み取り設定(すべてのファイルで共通)
+/// - `error_mode`: 読み込みに失敗したファイルがあった場合の動作
+/// - `on_file`: ファイルのパスと、そのファイルのグループごとの値を受け取る
+pub fn read_xml_files_each<P, F>(
+    paths: &[P],
+    read_config: &XmlReadConfig,
+    error_mode: BatchErrorMode,
+    on_file: F,
+) -> Result<BatchSummary, Box<dyn Error>>
+where
+    P: AsRef<Path> + Sync,
+    F: FnMut(&Path, Vec<Vec<OutPutTagValue>>) -> Result<(), Box<dyn Error>>,
+{
+    let thread_count = thread::available_parallelism()
+        .map(|x| x.get())
+        .unwrap_or(1);
+    read_xml_files_each_with_thread_count(paths, read_config, error_mode, on_file, thread_count)
+}
+
+/// `read_xml_files_each`の本体です。(`thread_count`はスレッドの数の上限)
+fn read_xml_files_each_with_thread_count<P, F>(
+    paths: &[P],
+    read_config: &XmlReadConfig,
+    error_mode: BatchErrorMode,
+    mut on_file: F,
+    thread_count: usize,
+) -> Result<BatchSummary, Box<dyn Error>>
+where
+    P: AsRef<Path> + Sync,
+    F: FnMut(&Path, Vec<Vec<OutPutTagValue>>) -> Result<(), Box<dyn Error>>,
+{
+    let thread_count = thread_count.min(paths.len());
+    let files_ahead = thread_count * FILES_AHEAD_PER_THREAD;
+    let next_index = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    // `on_file`の処理が終わったファイルの数(読み込みを待っているスレッドに通知する)
+    let finished_count = (Mutex::new(0usize), Condvar::new());
+    let mut summary = BatchSummary::default();
+
+    thread::scope(|scope| {
+        // `Box<dyn Error>`はスレッド間で受け渡せないため、エラーは文字列にする
+        let (sender, receiver) =
+            mpsc::sync_channel::<(usize, Result<Vec<Vec<OutPutTagValue>>, String>)>(thread_count);
+        for _ in 0..thread_count {
+            let sender = sender.clone();
+            let (next_index, stop, finished_count) = (&next_index, &stop, &finished_count);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(index) else {
+                        break;
+                    };
+                    // 先に読み込めるファイルの数を超える場合は、呼び出し元の処理が進むまで待つ
+                    let (count, condvar) = finished_count;
+                    drop(
+                        condvar
+                            .wait_while(count.lock().unwrap(), |count| {
+                                index >= *count + files_ahead && !stop.load(Ordering::Relaxed)
+                            })
+                            .unwrap(),
+                    );
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let result =
+                        read_xml_file(path.as_ref(), read_config).map_err(|e| e.to_string());
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        let notify_finished = |count: usize| {
+            let (finished, condvar) = &finished_count;
+            *finished.lock().unwrap() = count;
+            condvar.notify_all();
+        };
+
+        // 読み込みが終わった順に届くため、指定した順に並べ替えて処理する
+        let mut pending: HashMap<usize, Result<Vec<Vec<OutPutTagValue>>, String>> = HashMap::new();
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&summary.file_count) {
+                let path = paths[summary.file_count].as_ref();
+                summary.file_count += 1;
+                let result = match result {
+                    Ok(groups) => {
+                        summary.group_count += groups.len();
+                        on_file(path, groups)
+                    }
+                    Err(message) if error_mode == BatchErrorMode::Continue => {
+                        log::warn!("{}: {}", path.display(), message);
+                        summary.failures.push(BatchFailure {
+                            path: path.to_path_buf(),
+                            message,
+                        });
+                        Ok(())
+                    }
+                    Err(message) => Err(format!("{}: {}", path.display(), message).into()),
+                };
+                if let Err(e) = result {
+                    stop.store(true, Ordering::Relaxed);
+                    notify_finished(summary.file_count);
+                    return Err(e);
+                }
+                notify_finished(summary.file_count);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+/// ファイルを1つ読み込みます。(圧縮されたファイルは展開して読み込む)
+fn read_xml_file(
+    path: &Path,
+    read_config: &XmlReadConfig,
+) -> Result<Vec<Vec<OutPutTagValue>>, Box<dyn Error>> {
+    read_xml(&mut open_xml_file(path)?, read_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::xml_read_config::TagValueName;
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    #[test_log::test]
+    fn test_read_xml_files() {
+        let config = build_read_config(
+            "tv/programme",
+            vec![("title", TagValueName::new_tag_text())],
+        );
+        let paths = [XML_FILE, "test_xml/not_found.xml", XML_FILE];
+        let expected = read_xml_file(Path::new(XML_FILE), &config).unwrap();
+
+        let (files, summary) = read_xml_files(&paths, &config, BatchErrorMode::Continue).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().all(|x| x.get_path() == Path::new(XML_FILE)));
+        assert_eq!(files[1].get_groups(), expected.as_slice());
+        assert_eq!(summary.get_file_count(), 3);
+        assert_eq!(summary.get_succeeded_file_count(), 2);
+        assert_eq!(summary.get_group_count(), expected.len() * 2);
+        assert!(!summary.is_success());
+        // パスはメッセージに含まず、`Abort`の場合のエラーにのみ1回付ける
+        let not_found = std::fs::File::open(paths[1]).unwrap_err().to_string();
+        assert_eq!(
+            summary.get_failures()[0].get_path(),
+            Path::new("test_xml/not_found.xml")
+        );
+        assert_eq!(summary.get_failures()[0].get_message(), not_found);
+
+        let error = read_xml_files(&paths, &config, BatchErrorMode::Abort).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("test_xml/not_found.xml: {}", not_found)
+        );
+
+        // 先に読み込めるファイルの数より多いファイルでも、すべて指定した順に処理する
+        let mut many_paths = vec![XML_FILE; 50];
+        many_paths[40] = "test_xml/not_found.xml";
+        for thread_count in [1, 4] {
+            let mut file_count = 0;
+            let summary = read_xml_files_each_with_thread_count(
+                &many_paths,
+                &config,
+                BatchErrorMode::Continue,
+                |_, groups| {
+                    assert_eq!(groups, expected);
+                    file_count += 1;
+                    Ok(())
+                },
+                thread_count,
+            )
+            .unwrap();
+            assert_eq!(file_count, 49);
+            assert_eq!(summary.get_file_count(), 50);
+            assert_eq!(summary.get_group_count(), expected.len() * 49);
+
+            let error = read_xml_files_each_with_thread_count(
+                &many_paths,
+                &config,
+                BatchErrorMode::Abort,
+                |_, _| Ok(()),
+                thread_count,
+            )
+            .unwrap_err();
+            assert!(error.to_string().starts_with("test_xml/not_found.xml: "));
+        }
+
+        #[cfg(feature = "glob")]
+        {
+            let (files, summary) =
+                read_xml_glob("test_xml/*.xml", &config, BatchErrorMode::Abort).unwrap();
+            assert!(summary.is_success());
+            assert!(files.iter().any(|x| x.get_path() == Path::new(XML_FILE)));
+        }
+    }
+}
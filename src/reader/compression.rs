@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// 入力の圧縮形式です。(先頭のバイト列で判定)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// 圧縮なし
+    None,
+    /// gzip(`.gz`、`gzip`フィーチャーが必要)
+    Gzip,
+    /// Zstandard(`.zst`、`zstd`フィーチャーが必要)
+    Zstd,
+    /// bzip2(`.bz2`、`bzip2`フィーチャーが必要)
+    Bzip2,
+}
+
+impl Compression {
+    /// 先頭のバイト列(マジックナンバー)から圧縮形式を判定します。
+    pub fn detect(head: &[u8]) -> Self {
+        if head.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else if head.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else {
+            Compression::None
+        }
+    }
+
+    /// 圧縮形式の名前(フィーチャー名と同じ)を取得します。
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Bzip2 => "bzip2",
+        }
+    }
+}
+
+/// XMLファイルを開きます。
+///
+/// 圧縮されたファイル(`.xml.gz`・`.xml.zst`・`.xml.bz2`)は、拡張子ではなく先頭のバイト列で判定し、
+/// 読み込みながら展開します。
+/// 対応するフィーチャーが有効でない圧縮形式の場合は、エラーを返します。
+/// (エラーメッセージにパスは含まないため、必要であれば呼び出し元で追加する)
+pub fn open_xml_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<quick_xml::Reader<Box<dyn BufRead + Send>>, Box<dyn Error>> {
+    let reader = decode_xml_input(BufReader::new(File::open(path)?))?;
+    Ok(quick_xml::Reader::from_reader(reader))
+}
+
+/// 入力の圧縮形式を判定し、展開しながら読み込むリーダーを返します。(圧縮されていない場合はそのまま返す)
+pub fn decode_xml_input<R>(mut reader: R) -> Result<Box<dyn BufRead + Send>, Box<dyn Error>>
+where
+    R: BufRead + Send + 'static,
+{
+    // `fill_buf`は読み込んだバイト列を消費しないため、判定後も先頭から読み込める
+    let compression = Compression::detect(reader.fill_buf()?);
+    match compression {
+        Compression::None => Ok(Box::new(reader)),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        ))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(
+            zstd::stream::read::Decoder::with_buffer(reader)?,
+        ))),
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2 => Ok(Box::new(BufReader::new(
+            bzip2::bufread::MultiBzDecoder::new(reader),
+        ))),
+        #[allow(unreachable_patterns)]
+        _ => Err(format!(
+            "input is {}-compressed; enable the `{}` feature to read it",
+            compression.get_name(),
+            compression.get_name()
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::xml_read_config::TagValueName;
+    use crate::reader::xml_reader::read_xml;
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    #[test_log::test]
+    fn test_detect_compression() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Compression::Zstd
+        );
+        assert_eq!(Compression::detect(b"BZh91AY&SY"), Compression::Bzip2);
+        assert_eq!(Compression::detect(b"<?xml"), Compression::None);
+        assert_eq!(Compression::detect(b""), Compression::None);
+    }
+
+    #[test_log::test]
+    fn test_open_xml_file_compressed() {
+        let xml = std::fs::read(XML_FILE).unwrap();
+        let config = build_read_config(
+            "tv/programme",
+            vec![("title", TagValueName::new_tag_text())],
+        );
+        let expected = read_xml(&mut open_xml_file(XML_FILE).unwrap(), &config).unwrap();
+        assert!(!expected.is_empty());
+
+        // 圧縮なしの場合と、有効なフィーチャーの圧縮形式で同じ結果になることを確認する
+        let inputs = [
+            (Compression::None, xml.clone()),
+            #[cfg(feature = "gzip")]
+            (Compression::Gzip, {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&xml).unwrap();
+                encoder.finish().unwrap()
+            }),
+            #[cfg(feature = "zstd")]
+            (
+                Compression::Zstd,
+                zstd::stream::encode_all(xml.as_slice(), 0).unwrap(),
+            ),
+            #[cfg(feature = "bzip2")]
+            (Compression::Bzip2, {
+                use std::io::Read;
+                let mut data = Vec::new();
+                bzip2::read::BzEncoder::new(xml.as_slice(), bzip2::Compression::default())
+                    .read_to_end(&mut data)
+                    .unwrap();
+                data
+            }),
+        ];
+        for (compression, data) in inputs {
+            assert_eq!(Compression::detect(&data), compression);
+            let reader = decode_xml_input(std::io::Cursor::new(data)).unwrap();
+            let mut reader = quick_xml::Reader::from_reader(reader);
+            assert_eq!(read_xml(&mut reader, &config).unwrap(), expected);
+        }
+
+        #[cfg(not(feature = "gzip"))]
+        {
+            let error = decode_xml_input(std::io::Cursor::new(vec![0x1f, 0x8b, 0x08])).err();
+            assert_eq!(
+                error.unwrap().to_string(),
+                "input is gzip-compressed; enable the `gzip` feature to read it"
+            );
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+#[cfg(any(feature = "toml", feature = "json"))]
+use std::error::Error;
+#[cfg(any(feature = "toml", feature = "json"))]
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::typed_value::ValueKind;
+use super::util::parse_tag_path;
+use super::xml_read_config::{AttributeNamePattern, TagValueName, XmlReadConfig, XmlTagReadConfig};
+
+/// 設定ファイル上の`XmlReadConfig`の表現です。
+///
+/// ```toml
+/// tag_group_hierarchy = "tv/programme"
+/// document_values = ["comment", "?xml-stylesheet"]
+///
+/// [[tags]]
+/// tag_hierarchy = "tv/programme"
+/// values = ["@start", "@stop"]
+///
+/// [[tags]]
+/// tag_hierarchy = "tv/programme/title"
+/// values = ["text", "@lang"]
+///
+/// [[tags]]
+/// tag_hierarchy = "tv/programme/video/aspect"
+/// values = ["text"]
+/// types = { text = "enum:4:3|16:9" }
+///
+/// [[tags]]
+/// tag_hierarchy = "tv/channel"
+/// values = ["@*"]
+/// exclude_attributes = ["id"]
+/// attribute_pattern = "^(transport_stream_id|service_id)$"
+/// ```
+///
+/// - タグ階層は`/`区切りのパスで記述します。
+/// - 取得する項目は、タグのテキストを`text`、属性を`@属性名`、タグのXMLそのものを`inner_xml`・`outer_xml`で記述します。
+/// - `types`には、項目ごとの値の種類(`ValueKind`の文字列表現)を記述します。(省略時は文字列)
+/// - `document_values`には、文書レベル(ルートタグの外)から取得するコメント(`comment`)・処理命令(`?ターゲット名`)を記述します。
+/// - `@*`はすべての属性を表します。`exclude_attributes`で除外する属性名を、
+///   `attribute_pattern`で取得する属性名の正規表現を指定できます。(いずれも`@*`がある場合のみ)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(in crate::reader) struct XmlReadConfigFile {
+    /// グループ化の単位となるタグのパス
+    tag_group_hierarchy: String,
+    /// 文書レベルから取得する項目のリスト
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    document_values: Vec<TagValueName>,
+    /// タグごとの読み取り設定
+    #[serde(default)]
+    tags: Vec<XmlTagReadConfigFile>,
+}
+
+/// 設定ファイル上の`XmlTagReadConfig`の表現です。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(in crate::reader) struct XmlTagReadConfigFile {
+    /// タグのパス
+    tag_hierarchy: String,
+    /// 取得する項目のリスト
+    #[serde(default)]
+    values: Vec<TagValueName>,
+    /// 項目ごとの値の種類
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    types: BTreeMap<TagValueName, ValueKind>,
+    /// `@*`で取得しない属性名
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    exclude_attributes: BTreeSet<String>,
+    /// `@*`で取得する属性名の正規表現
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attribute_pattern: Option<String>,
+}
+
+impl Serialize for TagValueName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TagValueName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for ValueKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl TryFrom<XmlTagReadConfigFile> for XmlTagReadConfig {
+    type Error = String;
+
+    fn try_from(file: XmlTagReadConfigFile) -> Result<Self, Self::Error> {
+        let tag_hierarchy = parse_tag_path(&file.tag_hierarchy);
+        if tag_hierarchy.is_empty() {
+            return Err(format!("tag_hierarchy {:?} is empty", file.tag_hierarchy));
+        }
+        let mut target_tag_value_names = HashSet::new();
+        for tag_value_name in file.values {
+            if target_tag_value_names.contains(&tag_value_name) {
+                return Err(format!(
+                    "tag_hierarchy {:?}: value {:?} is duplicated",
+                    file.tag_hierarchy,
+                    tag_value_name.to_string()
+                ));
+            }
+            target_tag_value_names.insert(tag_value_name);
+        }
+        let mut config = XmlTagReadConfig::new(tag_hierarchy, target_tag_value_names);
+        for (tag_value_name, value_kind) in file.types {
+            config
+                .set_value_kind(tag_value_name.clone(), value_kind)
+                .map_err(|_| {
+                    format!(
+                        "type of {:?} is declared but it is not in values",
+                        tag_value_name.to_string()
+                    )
+                })?;
+        }
+        config.set_excluded_attributes(file.exclude_attributes)?;
+        let attribute_pattern = file
+            .attribute_pattern
+            .as_deref()
+            .map(AttributeNamePattern::new)
+            .transpose()?;
+        config.set_attribute_name_pattern(attribute_pattern)?;
+        Ok(config)
+    }
+}
+
+impl From<XmlTagReadConfig> for XmlTagReadConfigFile {
+    fn from(config: XmlTagReadConfig) -> Self {
+        let mut values: Vec<TagValueName> =
+            config.get_target_tag_value_names().into_iter().collect();
+        values.sort_by_key(|tag_value_name| tag_value_name.to_string());
+        XmlTagReadConfigFile {
+            tag_hierarchy: config.get_tag_hierarchy().join("/"),
+            values,
+            types: config.get_value_kinds(),
+            exclude_attributes: config.get_excluded_attributes(),
+            attribute_pattern: config
+                .get_attribute_name_pattern()
+                .map(|x| x.as_str().to_string()),
+        }
+    }
+}
+
+impl TryFrom<XmlReadConfigFile> for XmlReadConfig {
+    type Error = String;
+
+    fn try_from(file: XmlReadConfigFile) -> Result<Self, Self::Error> {
+        let tag_group_hierarchy = parse_tag_path(&file.tag_group_hierarchy);
+        if tag_group_hierarchy.is_empty() {
+            return Err(format!(
+                "tag_group_hierarchy {:?} is empty",
+                file.tag_group_hierarchy
+            ));
+        }
+        let mut config = XmlReadConfig::new(tag_group_hierarchy);
+        config
+            .set_document_tag_value_names(file.document_values.into_iter().collect())
+            .map_err(|e| format!("document_values: {}", e))?;
+        let mut seen: Vec<Vec<String>> = Vec::new();
+        for (i, tag) in file.tags.into_iter().enumerate() {
+            let entry = format!("tags[{}] ({})", i, tag.tag_hierarchy);
+            let tag_read_config =
+                XmlTagReadConfig::try_from(tag).map_err(|e| format!("{}: {}", entry, e))?;
+            let tag_hierarchy = tag_read_config.get_tag_hierarchy();
+            if let Some(j) = seen.iter().position(|x| *x == tag_hierarchy) {
+                return Err(format!(
+                    "{}: tag_hierarchy is already defined in tags[{}]",
+                    entry, j
+                ));
+            }
+            config
+                .insert_xml_tag_read_config_to_hash_map(&tag_read_config)
+                .map_err(|e| format!("{}: {}", entry, e))?;
+            seen.push(tag_hierarchy);
+        }
+        Ok(config)
+    }
+}
+
+impl From<XmlReadConfig> for XmlReadConfigFile {
+    fn from(config: XmlReadConfig) -> Self {
+        let mut tags: Vec<XmlTagReadConfigFile> = config
+            .get_tag_hierarchy_map()
+            .into_values()
+            .map(XmlTagReadConfigFile::from)
+            .collect();
+        tags.sort_by(|a, b| a.tag_hierarchy.cmp(&b.tag_hierarchy));
+        let mut document_values: Vec<TagValueName> =
+            config.get_document_tag_value_names().into_iter().collect();
+        document_values.sort_by_key(|tag_value_name| tag_value_name.to_string());
+        XmlReadConfigFile {
+            tag_group_hierarchy: config.get_tag_group_hierarchy().join("/"),
+            document_values,
+            tags,
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl XmlReadConfig {
+    /// TOML形式の文字列から`XmlReadConfig`を読み込みます。
+    ///
+    /// 設定に誤りがある場合は、該当する項目(`tags[1] (tv/programme/title)`など)を含むエラーを返します。
+    pub fn from_toml_str(s: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// `XmlReadConfig`をTOML形式の文字列に変換します。
+    pub fn to_toml_string(&self) -> Result<String, Box<dyn Error>> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+#[cfg(feature = "json")]
+impl XmlReadConfig {
+    /// JSON形式の文字列から`XmlReadConfig`を読み込みます。
+    ///
+    /// 設定に誤りがある場合は、該当する項目(`tags[1] (tv/programme/title)`など)を含むエラーを返します。
+    pub fn from_json_str(s: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// `XmlReadConfig`をJSON形式の文字列に変換します。
+    pub fn to_json_string(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(any(feature = "toml", feature = "json"))]
+impl XmlReadConfig {
+    /// 設定ファイルから`XmlReadConfig`を読み込みます。
+    ///
+    /// ファイルの形式は拡張子(`.toml`または`.json`)で判断します。
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)?;
+        let extension = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let res = match extension.as_str() {
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml_str(&s),
+            #[cfg(feature = "json")]
+            "json" => Self::from_json_str(&s),
+            _ => {
+                let s_err = format!("UNSUPPORTED CONFIG FILE EXTENSION. path {:?}", path);
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    s_err,
+                )));
+            }
+        };
+        res.map_err(|e| format!("FAILED TO READ CONFIG FILE. path {:?} : {}", path, e).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::util::string_vec;
+
+    fn programme_config_file() -> XmlReadConfigFile {
+        XmlReadConfigFile {
+            tag_group_hierarchy: "tv/programme".to_string(),
+            document_values: vec!["comment".parse().unwrap()],
+            tags: vec![
+                XmlTagReadConfigFile {
+                    tag_hierarchy: "tv/programme".to_string(),
+                    values: vec!["@start".parse().unwrap(), "@stop".parse().unwrap()],
+                    types: BTreeMap::from([(
+                        "@start".parse().unwrap(),
+                        "datetime:%Y%m%d%H%M%S %z".parse().unwrap(),
+                    )]),
+                    exclude_attributes: BTreeSet::new(),
+                    attribute_pattern: None,
+                },
+                XmlTagReadConfigFile {
+                    tag_hierarchy: "tv/programme/title".to_string(),
+                    values: vec!["@lang".parse().unwrap(), "text".parse().unwrap()],
+                    types: BTreeMap::new(),
+                    exclude_attributes: BTreeSet::new(),
+                    attribute_pattern: None,
+                },
+            ],
+        }
+    }
+
+    #[test_log::test]
+    fn test_config_file_round_trip() {
+        let config = XmlReadConfig::try_from(programme_config_file()).unwrap();
+        assert_eq!(
+            config.get_tag_group_hierarchy(),
+            string_vec(vec!["tv", "programme"])
+        );
+        let title = &config.get_tag_hierarchy_map()[&string_vec(vec!["tv", "programme", "title"])];
+        assert!(
+            title
+                .get_target_tag_value_names()
+                .contains(&TagValueName::new_tag_text())
+        );
+        assert_eq!(XmlReadConfigFile::from(config), programme_config_file());
+    }
+
+    #[test_log::test]
+    fn test_config_file_error_names_entry() {
+        let mut file = programme_config_file();
+        file.tags[1].tag_hierarchy = "tv/channel/display-name".to_string();
+        let err = XmlReadConfig::try_from(file).unwrap_err();
+        assert!(
+            err.starts_with("tags[1] (tv/channel/display-name)"),
+            "{}",
+            err
+        );
+
+        let mut file = programme_config_file();
+        file.tags[1].tag_hierarchy = "tv/programme".to_string();
+        let err = XmlReadConfig::try_from(file).unwrap_err();
+        assert!(err.contains("already defined in tags[0]"), "{}", err);
+
+        let mut file = programme_config_file();
+        file.tags[1].exclude_attributes = BTreeSet::from(["lang".to_string()]);
+        let err = XmlReadConfig::try_from(file).unwrap_err();
+        assert!(err.contains("only valid with @*"), "{}", err);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test_log::test]
+    fn test_from_toml_str() {
+        let config = XmlReadConfig::from_toml_str(
+            r#"
+            tag_group_hierarchy = "tv/programme"
+            document_values = ["comment"]
+
+            [[tags]]
+            tag_hierarchy = "tv/programme"
+            values = ["@start", "@stop"]
+            types = { "@start" = "datetime:%Y%m%d%H%M%S %z" }
+
+            [[tags]]
+            tag_hierarchy = "tv/programme/title"
+            values = ["text", "@lang"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            XmlReadConfig::try_from(programme_config_file()).unwrap()
+        );
+
+        let err = XmlReadConfig::from_toml_str(
+            r#"
+            tag_group_hierarchy = "tv/programme"
+
+            [[tags]]
+            tag_hierarchy = "tv/programme/title"
+            values = ["txt"]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("\"txt\""), "{}", err);
+
+        let err = XmlReadConfig::from_toml_str(
+            r#"
+            tag_group_hierarchy = "tv/programme"
+
+            [[tags]]
+            tag_hierarchy = "tv/programme"
+            values = ["@start"]
+            types = { "@event_id" = "integer" }
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("\"@event_id\""), "{}", err);
+    }
+
+    #[cfg(feature = "json")]
+    #[test_log::test]
+    fn test_json_round_trip() {
+        let config = XmlReadConfig::try_from(programme_config_file()).unwrap();
+        let json = config.to_json_string().unwrap();
+        assert_eq!(XmlReadConfig::from_json_str(&json).unwrap(), config);
+    }
+}
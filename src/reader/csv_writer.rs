@@ -0,0 +1,277 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use super::group::find_values;
+use super::output_tag_value::OutPutTagValue;
+use super::xml_read_config::{TagValueName, XmlReadConfig};
+use super::xml_reader::read_xml_each;
+
+/// CSV/TSVの列の定義です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CsvColumn {
+    /// 見出し
+    header: String,
+    /// 値を取得するタグ階層
+    tag_hierarchy: Vec<String>,
+    /// 値を取得する項目
+    tag_value_name: TagValueName,
+}
+
+impl CsvColumn {
+    /// 新しい`CsvColumn`を作成します。
+    ///
+    /// # 引数
+    /// - `header`: 見出し
+    /// - `tag_hierarchy`: 値を取得するタグ階層
+    /// - `tag_value_name`: 値を取得する項目
+    pub fn new(header: String, tag_hierarchy: Vec<String>, tag_value_name: TagValueName) -> Self {
+        CsvColumn {
+            header,
+            tag_hierarchy,
+            tag_value_name,
+        }
+    }
+
+    /// 見出しを取得します。
+    pub fn get_header(&self) -> &str {
+        &self.header
+    }
+
+    /// グループから、この列の値を出現順に取得します。
+    pub fn pick_up_values(&self, group: &[OutPutTagValue]) -> Vec<String> {
+        find_values(group, &self.tag_hierarchy, &self.tag_value_name)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// グループを1行としてCSV/TSVを書き出します。
+///
+/// - 1つの列に複数の値がある場合は、`multi_value_separator`で連結します。(既定値は`|`)
+/// - 区切り文字・`"`・改行を含む値は`"`で囲み、値の中の`"`は`""`にします。
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    columns: Vec<CsvColumn>,
+    delimiter: char,
+    multi_value_separator: String,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// カンマ区切り(CSV)の`CsvWriter`を作成します。
+    pub fn new(writer: W, columns: Vec<CsvColumn>) -> Self {
+        CsvWriter {
+            writer,
+            columns,
+            delimiter: ',',
+            multi_value_separator: "|".to_string(),
+        }
+    }
+
+    /// タブ区切り(TSV)の`CsvWriter`を作成します。
+    pub fn new_tsv(writer: W, columns: Vec<CsvColumn>) -> Self {
+        let mut csv_writer = Self::new(writer, columns);
+        csv_writer.set_delimiter('\t');
+        csv_writer
+    }
+
+    /// 区切り文字を設定します。
+    pub fn set_delimiter(&mut self, delimiter: char) {
+        self.delimiter = delimiter;
+    }
+
+    /// 1つの列に複数の値がある場合の連結文字列を設定します。
+    pub fn set_multi_value_separator(&mut self, multi_value_separator: &str) {
+        self.multi_value_separator = multi_value_separator.to_string();
+    }
+
+    /// 列の定義を取得します。
+    pub fn get_columns(&self) -> &[CsvColumn] {
+        &self.columns
+    }
+
+    /// 見出し行を書き出します。
+    pub fn write_header(&mut self) -> Result<(), Box<dyn Error>> {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| column.header.clone())
+            .collect();
+        self.write_record(&fields)
+    }
+
+    /// 1グループ分の`OutPutTagValue`を1行として書き出します。
+    pub fn write_group(&mut self, group: &[OutPutTagValue]) -> Result<(), Box<dyn Error>> {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| {
+                column
+                    .pick_up_values(group)
+                    .join(&self.multi_value_separator)
+            })
+            .collect();
+        self.write_record(&fields)
+    }
+
+    /// バッファに残っている内容を書き出します。
+    pub fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// 内部のライターを取り出します。
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_record(&mut self, fields: &[String]) -> Result<(), Box<dyn Error>> {
+        let mut line = String::new();
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            line.push_str(&self.quote(field));
+        }
+        line.push_str("\r\n");
+        self.writer.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn quote(&self, field: &str) -> String {
+        if field.contains(self.delimiter)
+            || field.contains('"')
+            || field.contains('\r')
+            || field.contains('\n')
+        {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+/// XMLを順次読み込み、見出し行とすべてのグループをCSV/TSVとして書き出します。
+///
+/// グループを1つずつ書き出すため、結果をすべてメモリに保持しません。
+///
+/// # 戻り値
+/// - 書き出したグループの数(見出し行を除く)
+pub fn write_xml_as_csv<R: BufRead, W: Write>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    csv_writer: &mut CsvWriter<W>,
+) -> Result<usize, Box<dyn Error>> {
+    csv_writer.write_header()?;
+    let mut count = 0;
+    read_xml_each(reader, read_config, |group| {
+        count += 1;
+        csv_writer.write_group(&group)
+    })?;
+    csv_writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::reader::{util::string_vec, xml_read_config::XmlTagReadConfig};
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    fn channel_columns() -> (XmlReadConfig, Vec<CsvColumn>) {
+        let channel = string_vec(vec!["tv", "channel"]);
+        let display_name = string_vec(vec!["tv", "channel", "display-name"]);
+        let mut config = XmlReadConfig::new(channel.clone());
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                channel.clone(),
+                HashSet::from_iter(vec![
+                    TagValueName::new_attribute("id".to_string()),
+                    TagValueName::new_attribute("tp".to_string()),
+                ]),
+            ))
+            .unwrap();
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                display_name.clone(),
+                HashSet::from_iter(vec![
+                    TagValueName::new_tag_text(),
+                    TagValueName::new_attribute("lang".to_string()),
+                ]),
+            ))
+            .unwrap();
+        let columns = vec![
+            CsvColumn::new(
+                "id".to_string(),
+                channel.clone(),
+                TagValueName::new_attribute("id".to_string()),
+            ),
+            CsvColumn::new(
+                "name, lang".to_string(),
+                display_name.clone(),
+                TagValueName::new_tag_text(),
+            ),
+            CsvColumn::new(
+                "missing".to_string(),
+                channel,
+                TagValueName::new_attribute("missing".to_string()),
+            ),
+        ];
+        (config, columns)
+    }
+
+    #[test_log::test]
+    fn test_write_xml_as_csv() {
+        let (config, columns) = channel_columns();
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let mut csv_writer = CsvWriter::new(Vec::new(), columns);
+        let count = write_xml_as_csv(&mut reader, &config, &mut csv_writer).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(csv_writer.into_inner()).unwrap(),
+            "id,\"name, lang\",missing\r\n\
+             GR2_1032,CHANNEL_NAME_1_1,\r\n\
+             GR2_1034,CHANNEL_NAME_1_2,\r\n"
+        );
+    }
+
+    #[test_log::test]
+    fn test_write_group_tsv_multi_value() {
+        let programme = string_vec(vec!["tv", "programme"]);
+        let category = string_vec(vec!["tv", "programme", "category"]);
+        let mut config = XmlReadConfig::new(programme.clone());
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                programme,
+                HashSet::new(),
+            ))
+            .unwrap();
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                category.clone(),
+                HashSet::from_iter(vec![TagValueName::new_tag_text()]),
+            ))
+            .unwrap();
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let mut csv_writer = CsvWriter::new_tsv(
+            Vec::new(),
+            vec![CsvColumn::new(
+                "category".to_string(),
+                category,
+                TagValueName::new_tag_text(),
+            )],
+        );
+        csv_writer.set_multi_value_separator("\t");
+        write_xml_as_csv(&mut reader, &config, &mut csv_writer).unwrap();
+        assert_eq!(
+            String::from_utf8(csv_writer.into_inner()).unwrap(),
+            "category\r\n\"CAT_1\tCAT_2\"\r\n\"CAT_1\tCAT_2\"\r\n"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::io::BufRead;
+
+use quick_xml::events::{BytesStart, Event};
+
+use super::util::{BytesTag, get_elm_name};
+use super::xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig};
+
+/// `discover`で保持する値の例の既定の最大数です。
+pub const DEFAULT_SAMPLE_LIMIT: usize = 3;
+
+/// 属性の出現状況です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSummary {
+    /// 属性名
+    name: String,
+    /// 出現回数
+    count: usize,
+    /// 値の例(重複なし、出現順)
+    samples: Vec<String>,
+}
+
+impl AttributeSummary {
+    /// 属性名を取得します。
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 出現回数を取得します。
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    /// 値の例を取得します。
+    pub fn get_samples(&self) -> &[String] {
+        &self.samples
+    }
+}
+
+/// タグ階層の出現状況です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HierarchySummary {
+    /// タグ階層
+    tag_hierarchy: Vec<String>,
+    /// 出現回数
+    count: usize,
+    /// 空白以外のテキストを持っていた回数
+    text_count: usize,
+    /// テキストの例(重複なし、出現順)
+    text_samples: Vec<String>,
+    /// 属性の出現状況(最初に現れた順)
+    attributes: Vec<AttributeSummary>,
+}
+
+impl HierarchySummary {
+    /// タグ階層を取得します。
+    pub fn get_tag_hierarchy(&self) -> &[String] {
+        &self.tag_hierarchy
+    }
+
+    /// 出現回数を取得します。
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    /// 空白以外のテキストを持っていた回数を取得します。
+    pub fn get_text_count(&self) -> usize {
+        self.text_count
+    }
+
+    /// テキストを持つことがあるかどうかを確認します。
+    pub fn has_text(&self) -> bool {
+        self.text_count > 0
+    }
+
+    /// テキストの例を取得します。
+    pub fn get_text_samples(&self) -> &[String] {
+        &self.text_samples
+    }
+
+    /// 属性の出現状況を取得します。
+    pub fn get_attributes(&self) -> &[AttributeSummary] {
+        &self.attributes
+    }
+
+    /// 指定した名前の属性の出現状況を取得します。
+    pub fn get_attribute(&self, name: &str) -> Option<&AttributeSummary> {
+        self.attributes.iter().find(|x| x.name == name)
+    }
+}
+
+/// 文書に含まれるタグ階層の一覧です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscoveryReport {
+    /// タグ階層の出現状況(最初に現れた順)
+    hierarchies: Vec<HierarchySummary>,
+    /// タグ階層から`hierarchies`の位置を引くためのハッシュマップ
+    index: HashMap<Vec<String>, usize>,
+}
+
+impl DiscoveryReport {
+    /// タグ階層の出現状況を最初に現れた順に取得します。
+    pub fn get_hierarchies(&self) -> &[HierarchySummary] {
+        &self.hierarchies
+    }
+
+    /// 指定したタグ階層の出現状況を取得します。
+    pub fn get(&self, tag_hierarchy: &[String]) -> Option<&HierarchySummary> {
+        self.index
+            .get(tag_hierarchy)
+            .map(|index| &self.hierarchies[*index])
+    }
+
+    /// グループ化の単位に適したタグ階層を推測します。
+    ///
+    /// ルートタグの子のうち、最も出現回数が多いタグ階層を返します。
+    /// 同数の場合は子孫のタグ階層の種類が多いもの(それも同じ場合は先に現れたもの)を返します。
+    /// ルートタグしかない場合はルートタグを返します。
+    pub fn suggest_tag_group_hierarchy(&self) -> Option<Vec<String>> {
+        let descendant_count = |tag_hierarchy: &[String]| {
+            self.hierarchies
+                .iter()
+                .filter(|x| x.tag_hierarchy.len() > tag_hierarchy.len())
+                .filter(|x| x.tag_hierarchy.starts_with(tag_hierarchy))
+                .count()
+        };
+        let mut suggestion: Option<(&HierarchySummary, (usize, usize))> = None;
+        for summary in self
+            .hierarchies
+            .iter()
+            .filter(|x| x.tag_hierarchy.len() == 2)
+        {
+            let score = (summary.count, descendant_count(&summary.tag_hierarchy));
+            if suggestion.is_none_or(|(_, x)| score > x) {
+                suggestion = Some((summary, score));
+            }
+        }
+        suggestion
+            .map(|(summary, _)| summary)
+            .or_else(|| self.hierarchies.first())
+            .map(|x| x.tag_hierarchy.clone())
+    }
+
+    /// 見つかったすべての属性とテキストを取得する`XmlReadConfig`を作成します。
+    ///
+    /// `tag_group_hierarchy`から始まるタグ階層がすべて設定されます。
+    /// グループ化の単位となるタグ階層の設定は、値がない場合も作成されます。
+    pub fn to_read_config(&self, tag_group_hierarchy: Vec<String>) -> XmlReadConfig {
+        let mut config = XmlReadConfig::new(tag_group_hierarchy.clone());
+        let mut has_group = false;
+        for summary in &self.hierarchies {
+            if !summary.tag_hierarchy.starts_with(&tag_group_hierarchy) {
+                continue;
+            }
+            has_group |= summary.tag_hierarchy == tag_group_hierarchy;
+            let mut target_tag_value_names: HashSet<TagValueName> = summary
+                .attributes
+                .iter()
+                .map(|x| TagValueName::new_attribute(x.name.clone()))
+                .collect();
+            if summary.has_text() {
+                target_tag_value_names.insert(TagValueName::new_tag_text());
+            }
+            // タグ階層は必ずグループのタグ階層から始まるためエラーにならない
+            let _ = config.insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                summary.tag_hierarchy.clone(),
+                target_tag_value_names,
+            ));
+        }
+        if !has_group {
+            let _ = config.insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                tag_group_hierarchy,
+                HashSet::new(),
+            ));
+        }
+        config
+    }
+
+    /// `suggest_tag_group_hierarchy`で推測したグループで`to_read_config`を呼び出します。
+    /// 文書にタグがない場合は`None`を返します。
+    pub fn to_starter_read_config(&self) -> Option<XmlReadConfig> {
+        self.suggest_tag_group_hierarchy()
+            .map(|tag_group_hierarchy| self.to_read_config(tag_group_hierarchy))
+    }
+
+    fn summary_mut(&mut self, tag_hierarchy: &[String]) -> &mut HierarchySummary {
+        let index = match self.index.get(tag_hierarchy) {
+            Some(index) => *index,
+            None => {
+                self.hierarchies.push(HierarchySummary {
+                    tag_hierarchy: tag_hierarchy.to_vec(),
+                    count: 0,
+                    text_count: 0,
+                    text_samples: Vec::new(),
+                    attributes: Vec::new(),
+                });
+                self.index
+                    .insert(tag_hierarchy.to_vec(), self.hierarchies.len() - 1);
+                self.hierarchies.len() - 1
+            }
+        };
+        &mut self.hierarchies[index]
+    }
+
+    fn put_start(
+        &mut self,
+        tag_hierarchy: &[String],
+        start: &BytesStart,
+        sample_limit: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        let summary = self.summary_mut(tag_hierarchy);
+        summary.count += 1;
+        for attr in start.attributes() {
+            let attr = attr?;
+            let name = String::from_utf8(attr.key.as_ref().to_vec())?;
+            let value = attr.unescape_value()?;
+            let attribute = match summary.attributes.iter_mut().position(|x| x.name == name) {
+                Some(index) => &mut summary.attributes[index],
+                None => {
+                    summary.attributes.push(AttributeSummary {
+                        name,
+                        count: 0,
+                        samples: Vec::new(),
+                    });
+                    summary.attributes.last_mut().unwrap()
+                }
+            };
+            attribute.count += 1;
+            put_sample(&mut attribute.samples, &value, sample_limit);
+        }
+        Ok(())
+    }
+}
+
+fn put_sample(samples: &mut Vec<String>, value: &str, sample_limit: usize) {
+    if samples.len() < sample_limit && !samples.iter().any(|x| x == value) {
+        samples.push(value.to_string());
+    }
+}
+
+/// 文書を読み込み、すべてのタグ階層と属性、出現回数、テキストの有無と値の例を収集します。
+///
+/// 値の例は、タグ階層・属性ごとに`DEFAULT_SAMPLE_LIMIT`個まで保持します。
+pub fn discover<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+) -> Result<DiscoveryReport, Box<dyn Error>> {
+    discover_with_sample_limit(reader, DEFAULT_SAMPLE_LIMIT)
+}
+
+/// `discover`と同じですが、値の例を保持する最大数を指定します。
+pub fn discover_with_sample_limit<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+    sample_limit: usize,
+) -> Result<DiscoveryReport, Box<dyn Error>> {
+    let mut report = DiscoveryReport::default();
+    let mut buf = Vec::new();
+    let mut current_tag_hierarchy: Vec<String> = Vec::new();
+    // 要素ごとにテキストを持っていたかどうか(同じ要素のテキストを重複して数えないため)
+    let mut text_flags: Vec<bool> = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => break,
+            Event::Start(start) => {
+                current_tag_hierarchy.push(get_elm_name(&BytesTag::Start(&start)));
+                text_flags.push(false);
+                report.put_start(&current_tag_hierarchy, &start, sample_limit)?;
+            }
+            Event::Empty(start) => {
+                current_tag_hierarchy.push(get_elm_name(&BytesTag::Start(&start)));
+                report.put_start(&current_tag_hierarchy, &start, sample_limit)?;
+                current_tag_hierarchy.pop();
+            }
+            Event::End(_) => {
+                current_tag_hierarchy.pop();
+                text_flags.pop();
+            }
+            Event::Text(e) => {
+                let text = e.unescape()?;
+                let text = text.trim();
+                if !text.is_empty() && !current_tag_hierarchy.is_empty() {
+                    let summary = report.summary_mut(&current_tag_hierarchy);
+                    if let Some(flag) = text_flags.last_mut()
+                        && !*flag
+                    {
+                        *flag = true;
+                        summary.text_count += 1;
+                    }
+                    put_sample(&mut summary.text_samples, text, sample_limit);
+                }
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::util::string_vec;
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    fn discover_sample() -> DiscoveryReport {
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        discover(&mut reader).unwrap()
+    }
+
+    #[test_log::test]
+    fn test_discover() {
+        let report = discover_sample();
+        assert_eq!(report.get_hierarchies()[0].get_tag_hierarchy(), ["tv"]);
+
+        let channel = report.get(&string_vec(vec!["tv", "channel"])).unwrap();
+        assert_eq!(channel.get_count(), 2);
+        assert!(!channel.has_text());
+        let names: Vec<&str> = channel
+            .get_attributes()
+            .iter()
+            .map(|x| x.get_name())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "id",
+                "transport_stream_id",
+                "original_network_id",
+                "service_id",
+                "tp"
+            ]
+        );
+        assert_eq!(
+            channel.get_attribute("id").unwrap().get_samples(),
+            ["GR2_1032", "GR2_1034"]
+        );
+        assert_eq!(channel.get_attribute("tp").unwrap().get_samples(), ["26"]);
+
+        let category = report
+            .get(&string_vec(vec!["tv", "programme", "category"]))
+            .unwrap();
+        assert_eq!(category.get_count(), 4);
+        assert_eq!(category.get_text_count(), 4);
+        assert_eq!(category.get_text_samples(), ["CAT_1", "CAT_2"]);
+
+        let audio_extdesc = report
+            .get(&string_vec(vec!["tv", "programme", "audio", "extdesc"]))
+            .unwrap();
+        assert_eq!(audio_extdesc.get_count(), 2);
+    }
+
+    #[test_log::test]
+    fn test_starter_read_config() {
+        let report = discover_sample();
+        let programme = string_vec(vec!["tv", "programme"]);
+        assert_eq!(
+            report.suggest_tag_group_hierarchy(),
+            Some(programme.clone())
+        );
+
+        let config = report.to_starter_read_config().unwrap();
+        assert_eq!(config.get_tag_group_hierarchy(), programme);
+        let tag_hierarchy_map = config.get_tag_hierarchy_map();
+        assert!(
+            tag_hierarchy_map[&programme]
+                .get_target_tag_value_names()
+                .contains(&TagValueName::new_attribute("duration".to_string()))
+        );
+        let title = &tag_hierarchy_map[&string_vec(vec!["tv", "programme", "title"])];
+        assert!(
+            title
+                .get_target_tag_value_names()
+                .contains(&TagValueName::new_tag_text())
+        );
+        assert!(!tag_hierarchy_map.contains_key(&string_vec(vec!["tv", "channel"])));
+    }
+}
@@ -0,0 +1,268 @@
+use std::error::Error;
+use std::io::BufRead;
+
+use super::output_tag_value::OutPutTagValue;
+use super::util::parse_tag_path;
+use super::xml_read_config::{TagValueName, XmlReadConfig};
+use super::xml_reader::read_xml_each;
+
+/// `read_xml`が返す1グループ分の`OutPutTagValue`をまとめたものです。
+///
+/// タグのパスは設定ファイルと同じ`/`区切りで指定します。
+/// - グループのタグ階層から始まるパス(例: `tv/programme/title`)は、そのままのタグ階層として扱います。
+/// - それ以外のパス(例: `title`、`video/aspect`)は、グループのタグ階層からの相対パスとして扱います。
+/// - 空のパス(`""`)は、グループのタグ自身を表します。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Group {
+    /// グループ化の単位となるタグ階層
+    tag_group_hierarchy: Vec<String>,
+    /// 値(文書の順)
+    values: Vec<OutPutTagValue>,
+}
+
+impl Group {
+    /// 新しい`Group`を作成します。値は文書の順に並べ替えます。
+    ///
+    /// # 引数
+    /// - `tag_group_hierarchy`: グループ化の単位となるタグ階層
+    /// - `values`: `read_xml`が返す1グループ分の`OutPutTagValue`
+    pub fn new(tag_group_hierarchy: Vec<String>, mut values: Vec<OutPutTagValue>) -> Self {
+        values.sort_by_key(|x| x.get_document_order());
+        Group {
+            tag_group_hierarchy,
+            values,
+        }
+    }
+
+    /// グループ化の単位となるタグ階層を取得します。
+    pub fn get_tag_group_hierarchy(&self) -> &[String] {
+        &self.tag_group_hierarchy
+    }
+
+    /// 値の数を取得します。
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// 値が空かどうかを確認します。
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// 値を文書の順(開始タグの順)に取得します。
+    pub fn iter(&self) -> std::slice::Iter<'_, OutPutTagValue> {
+        self.values.iter()
+    }
+
+    /// 値を文書の順に取り出します。
+    pub fn into_inner(self) -> Vec<OutPutTagValue> {
+        self.values
+    }
+
+    /// パスをタグ階層に変換します。
+    pub fn resolve_path(&self, path: &str) -> Vec<String> {
+        resolve_tag_path(&self.tag_group_hierarchy, path)
+    }
+
+    /// パスに該当するタグを文書の順に取得します。
+    pub fn elements(&self, path: &str) -> impl Iterator<Item = &OutPutTagValue> {
+        let tag_hierarchy = self.resolve_path(path);
+        find_elements(&self.values, tag_hierarchy)
+    }
+
+    /// パスに該当するタグの値のうち、最初のものを取得します。
+    ///
+    /// # 例
+    /// ```ignore
+    /// let title = group.first("title", &TagValueName::new_tag_text());
+    /// ```
+    pub fn first(&self, path: &str, tag_value_name: &TagValueName) -> Option<&str> {
+        self.elements(path)
+            .find_map(|x| x.get_value(tag_value_name))
+    }
+
+    /// パスに該当するタグの値を、文書の順にすべて取得します。
+    pub fn all(&self, path: &str, tag_value_name: &TagValueName) -> Vec<&str> {
+        find_values(&self.values, &self.resolve_path(path), tag_value_name).collect()
+    }
+
+    /// パスに該当するタグのテキストのうち、最初のものを取得します。
+    pub fn get_text(&self, path: &str) -> Option<&str> {
+        self.elements(path).find_map(|x| x.get_text())
+    }
+
+    /// パスに該当するタグの属性の値のうち、最初のものを取得します。
+    pub fn get_attr(&self, path: &str, name: &str) -> Option<&str> {
+        self.elements(path).find_map(|x| x.get_attribute(name))
+    }
+}
+
+impl From<Vec<OutPutTagValue>> for Group {
+    /// グループ内で最も短いタグ階層を、グループ化の単位となるタグ階層として`Group`を作成します。
+    fn from(values: Vec<OutPutTagValue>) -> Self {
+        Group::new(get_base_tag_hierarchy(&values), values)
+    }
+}
+
+impl<'a> IntoIterator for &'a Group {
+    type Item = &'a OutPutTagValue;
+    type IntoIter = std::slice::Iter<'a, OutPutTagValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// グループ内で最も短いタグ階層を、グループ化の単位となるタグ階層として取得します。
+///
+/// 読み取り設定がなく`read_xml`の結果だけがある場合の規則です。(`Group::from`・`pick_up_values`・`from_group`で共通)
+pub fn get_base_tag_hierarchy(group: &[OutPutTagValue]) -> Vec<String> {
+    group
+        .iter()
+        .map(|x| x.get_tag_hierarchy())
+        .min_by_key(|x| x.len())
+        .unwrap_or_default()
+}
+
+/// パスを、グループのタグ階層を基準としたタグ階層に変換します。(`Group::resolve_path`を参照)
+pub(crate) fn resolve_tag_path(tag_group_hierarchy: &[String], path: &str) -> Vec<String> {
+    let path = parse_tag_path(path);
+    if path.starts_with(tag_group_hierarchy) {
+        path
+    } else {
+        let mut tag_hierarchy = tag_group_hierarchy.to_vec();
+        tag_hierarchy.extend(path);
+        tag_hierarchy
+    }
+}
+
+/// グループから、タグ階層に一致するタグを出現順に取得します。
+fn find_elements(
+    group: &[OutPutTagValue],
+    tag_hierarchy: Vec<String>,
+) -> impl Iterator<Item = &OutPutTagValue> {
+    group
+        .iter()
+        .filter(move |x| x.get_tag_hierarchy() == tag_hierarchy)
+}
+
+/// グループから、タグ階層と項目に一致する値を出現順に取得します。
+/// (`Group`・`pick_up_values`・`CsvColumn`で共通)
+pub(crate) fn find_values<'a>(
+    group: &'a [OutPutTagValue],
+    tag_hierarchy: &[String],
+    tag_value_name: &TagValueName,
+) -> impl Iterator<Item = &'a str> {
+    find_elements(group, tag_hierarchy.to_vec()).flat_map(move |x| x.get_values(tag_value_name))
+}
+
+/// XMLを読み込み、`Group`が1つ完成するたびに`on_group`を呼び出します。
+pub fn read_groups_each<R, F>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    mut on_group: F,
+) -> Result<(), Box<dyn Error>>
+where
+    R: BufRead,
+    F: FnMut(Group) -> Result<(), Box<dyn Error>>,
+{
+    let tag_group_hierarchy = read_config.get_tag_group_hierarchy();
+    read_xml_each(reader, read_config, |values| {
+        on_group(Group::new(tag_group_hierarchy.clone(), values))
+    })
+}
+
+/// XMLを読み込み、すべての`Group`を返します。
+pub fn read_groups<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+) -> Result<Vec<Group>, Box<dyn Error>> {
+    let mut groups = Vec::new();
+    read_groups_each(reader, read_config, |group| {
+        groups.push(group);
+        Ok(())
+    })?;
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::util::string_vec;
+
+    #[test_log::test]
+    fn test_group_queries() {
+        let text = TagValueName::new_tag_text;
+        let config = build_read_config(
+            "tv/programme",
+            vec![
+                ("", TagValueName::new_attribute("start".to_string())),
+                ("title", text()),
+                ("category", text()),
+                ("category", TagValueName::new_attribute("lang".to_string())),
+                ("video/aspect", text()),
+                ("extdesc/item_description", text()),
+                ("extdesc/item", text()),
+            ],
+        );
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let groups = read_groups(&mut reader, &config).unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = &groups[0];
+
+        assert_eq!(group.get_text("title"), Some("TITLE_1_1"));
+        assert_eq!(group.get_text("tv/programme/title"), Some("TITLE_1_1"));
+        assert_eq!(group.get_attr("", "start"), Some("20241123090000 +0900"));
+        assert_eq!(group.all("category", &text()), vec!["CAT_1", "CAT_2"]);
+        assert_eq!(group.get_attr("category", "lang"), Some("ja_JP"));
+        assert_eq!(group.first("video/aspect", &text()), Some("16:9"));
+        assert_eq!(group.first("desc", &text()), None);
+
+        // 文書の順(グループのタグが最初、子タグは出現順)
+        let order: Vec<Vec<String>> = group.iter().map(|x| x.get_tag_hierarchy()).collect();
+        assert_eq!(order[0], string_vec(vec!["tv", "programme"]));
+        assert_eq!(order[1], string_vec(vec!["tv", "programme", "title"]));
+        let extdesc: Vec<&str> = group
+            .iter()
+            .filter(|x| x.get_tag_hierarchy().get(2).map(String::as_str) == Some("extdesc"))
+            .filter_map(|x| x.get_text())
+            .collect();
+        assert_eq!(&extdesc[..2], &["EXT_DESC_ITEM_NAME_1", "EXT_DESC_ITEM_1"]);
+
+        let from_vec = Group::from(group.clone().into_inner());
+        assert_eq!(&from_vec, group);
+    }
+
+    #[test_log::test]
+    fn test_group_all_multiple_values_in_element() {
+        let xml = r#"<tv><programme a="1" b="2"><!-- x --><!-- y --><desc>a<b/>c</desc></programme></tv>"#;
+        let config = build_read_config(
+            "tv/programme",
+            vec![
+                ("", TagValueName::new_all_attributes()),
+                ("", TagValueName::new_comment()),
+                ("desc", TagValueName::new_tag_text()),
+            ],
+        );
+        let mut reader = quick_xml::Reader::from_str(xml);
+        let groups = read_groups(&mut reader, &config).unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+
+        // 1つのタグに同じ項目の値が複数ある場合も、すべて取得する
+        assert_eq!(
+            group.all("", &TagValueName::new_all_attributes()),
+            vec!["1", "2"]
+        );
+        assert_eq!(
+            group.all("", &TagValueName::new_comment()),
+            vec![" x ", " y "]
+        );
+        assert_eq!(
+            group.all("desc", &TagValueName::new_tag_text()),
+            vec!["a", "c"]
+        );
+        assert_eq!(group.first("", &TagValueName::new_comment()), Some(" x "));
+    }
+}
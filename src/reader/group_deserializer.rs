@@ -0,0 +1,529 @@
+use std::fmt::{self, Display};
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+
+use super::group::get_base_tag_hierarchy;
+use super::output_tag_value::OutPutTagValue;
+use super::tag_value_type::TagValueType;
+
+/// グループを構造体へデシリアライズする際のエラーです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupDeserializeError {
+    message: String,
+}
+
+impl GroupDeserializeError {
+    fn with_key(self, key: &str) -> Self {
+        GroupDeserializeError {
+            message: format!("FIELD {:?}: {}", key, self.message),
+        }
+    }
+}
+
+impl Display for GroupDeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for GroupDeserializeError {}
+
+impl de::Error for GroupDeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        GroupDeserializeError {
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// `read_xml`が返す1グループ分の`OutPutTagValue`を、`#[derive(Deserialize)]`された構造体に変換します。
+///
+/// グループの基準となるタグ階層(`get_base_tag_hierarchy`を参照)からの相対パスをキーとして、
+/// 以下のようにフィールド名へ対応付けます。
+///
+/// | 値                                   | キー           |
+/// |--------------------------------------|----------------|
+/// | グループのタグのテキスト             | `$text`        |
+/// | グループのタグの属性`start`          | `@start`       |
+/// | 子タグ`title`のテキスト              | `title`        |
+/// | 子タグ`title`の属性`lang`            | `title/@lang`  |
+/// | 孫タグ`video/aspect`のテキスト       | `video/aspect` |
+/// | グループのタグの内側のXML            | `$inner_xml`   |
+/// | 子タグ`desc`全体のXML                | `desc/$outer_xml` |
+/// | グループのタグ直下のコメント         | `$comment`     |
+/// | 子タグ`desc`直下の処理命令`pi`       | `desc/?pi`     |
+///
+/// `@`や`/`を含むキーは`#[serde(rename = "@start")]`のように指定します。
+///
+/// - 値が存在しないフィールドは、`Option`であれば`None`になります。
+///   `Vec`などそれ以外のフィールドは、`#[serde(default)]`を指定した場合に既定値(`Vec`であれば空)になり、
+///   指定しない場合はエラーになります。
+/// - 同じキーの値が複数ある場合は、`Vec`で受け取ります。単一の値を受け取るフィールドに複数の値がある場合はエラーになります。
+/// - 数値や真偽値のフィールドは、文字列の値を解析して設定します。
+///
+/// # 引数
+/// - `group`: `read_xml`が返す1グループ分の`OutPutTagValue`
+///
+/// # 戻り値
+/// - 変換された構造体
+/// - 値が不足している場合や解析に失敗した場合は、フィールド名を含むエラー
+pub fn from_group<T: DeserializeOwned>(
+    group: &[OutPutTagValue],
+) -> Result<T, GroupDeserializeError> {
+    T::deserialize(GroupDeserializer::new(group))
+}
+
+/// `read_xml`の結果をすべて構造体に変換します。
+///
+/// 各グループは`from_group`と同じ規則で変換されます。
+pub fn from_groups<T: DeserializeOwned>(
+    groups: &[Vec<OutPutTagValue>],
+) -> Result<Vec<T>, GroupDeserializeError> {
+    groups.iter().map(|group| from_group(group)).collect()
+}
+
+/// グループの値をキーごとにまとめたものです。(キーは最初に現れた順)
+struct GroupDeserializer {
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl GroupDeserializer {
+    fn new(group: &[OutPutTagValue]) -> Self {
+        let base_len = get_base_tag_hierarchy(group).len();
+        let mut entries: Vec<(String, Vec<String>)> = Vec::new();
+        for out_put_tag_value in group {
+            let tag_hierarchy = out_put_tag_value.get_tag_hierarchy();
+            let relative_path = tag_hierarchy[base_len..].join("/");
+            for tag_value in out_put_tag_value.get_tag_values() {
+                let key = match tag_value.get_value_type() {
+                    TagValueType::TagText if relative_path.is_empty() => "$text".to_string(),
+                    TagValueType::TagText => relative_path.clone(),
+                    TagValueType::AttributeValue if relative_path.is_empty() => {
+                        format!("@{}", tag_value.get_tag_name())
+                    }
+                    TagValueType::AttributeValue => {
+                        format!("{}/@{}", relative_path, tag_value.get_tag_name())
+                    }
+                    TagValueType::ProcessingInstruction if relative_path.is_empty() => {
+                        tag_value.get_tag_value_name().to_string()
+                    }
+                    TagValueType::ProcessingInstruction => {
+                        format!("{}/{}", relative_path, tag_value.get_tag_value_name())
+                    }
+                    TagValueType::InnerXml | TagValueType::OuterXml | TagValueType::Comment => {
+                        let name = format!("${}", tag_value.get_tag_value_name());
+                        if relative_path.is_empty() {
+                            name
+                        } else {
+                            format!("{}/{}", relative_path, name)
+                        }
+                    }
+                };
+                let value = tag_value.get_value().to_string();
+                match entries.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, values)) => values.push(value),
+                    None => entries.push((key, vec![value])),
+                }
+            }
+        }
+        GroupDeserializer { entries }
+    }
+
+    fn take_entry(&mut self, key: &str) -> Option<(String, Vec<String>)> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for GroupDeserializer {
+    type Error = GroupDeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(EntriesAccess {
+            entries: self.entries.into_iter(),
+            current: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        // 値が存在するフィールドのみ渡す(存在しないフィールドは、serdeが`Option`・`#[serde(default)]`を処理する)
+        let entries: Vec<(String, Vec<String>)> = fields
+            .iter()
+            .filter_map(|field| self.take_entry(field))
+            .collect();
+        visitor.visit_map(EntriesAccess {
+            entries: entries.into_iter(),
+            current: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct EntriesAccess {
+    entries: std::vec::IntoIter<(String, Vec<String>)>,
+    current: Option<(String, Vec<String>)>,
+}
+
+impl<'de> MapAccess<'de> for EntriesAccess {
+    type Error = GroupDeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.entries.next() {
+            Some((key, values)) => {
+                let res = seed.deserialize(key.clone().into_deserializer());
+                self.current = Some((key, values));
+                res.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, values) = self
+            .current
+            .take()
+            .ok_or_else(|| de::Error::custom("VALUE IS REQUESTED BEFORE KEY"))?;
+        seed.deserialize(ValuesDeserializer { values })
+            .map_err(|e| e.with_key(&key))
+    }
+}
+
+/// 1つのキーに対応する値のリストを扱うデシリアライザです。
+struct ValuesDeserializer {
+    values: Vec<String>,
+}
+
+impl ValuesDeserializer {
+    fn single(self) -> Result<ValueDeserializer, GroupDeserializeError> {
+        let mut values = self.values;
+        match values.len() {
+            1 => Ok(ValueDeserializer {
+                value: values.remove(0),
+            }),
+            0 => Err(de::Error::custom("VALUE IS NOT FOUND")),
+            n => Err(de::Error::custom(format!(
+                "EXPECTED A SINGLE VALUE BUT FOUND {} VALUES {:?}",
+                n, values
+            ))),
+        }
+    }
+}
+
+macro_rules! forward_to_single_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValuesDeserializer {
+    type Error = GroupDeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values.len() == 1 {
+            self.single()?.deserialize_any(visitor)
+        } else {
+            self.deserialize_seq(visitor)
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.values.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ValuesAccess {
+            values: self.values.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_single_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_identifier
+    }
+
+    serde::forward_to_deserialize_any! {
+        unit unit_struct tuple_struct map struct
+    }
+}
+
+struct ValuesAccess {
+    values: std::vec::IntoIter<String>,
+}
+
+impl<'de> SeqAccess<'de> for ValuesAccess {
+    type Error = GroupDeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(ValueDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// 1つの文字列の値を扱うデシリアライザです。
+/// 数値や真偽値が要求された場合は文字列を解析します。
+struct ValueDeserializer {
+    value: String,
+}
+
+macro_rules! deserialize_parsed_value {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.value.trim().parse() {
+                    Ok(v) => visitor.$visit(v),
+                    Err(e) => Err(de::Error::custom(format!(
+                        "FAILED TO PARSE VALUE {:?}: {}",
+                        self.value, e
+                    ))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = GroupDeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let deserializer: de::value::StringDeserializer<GroupDeserializeError> =
+            self.value.into_deserializer();
+        deserializer.deserialize_enum(name, variants, visitor)
+    }
+
+    deserialize_parsed_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::reader::{
+        util::string_vec,
+        xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig},
+        xml_reader::read_xml,
+    };
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    #[derive(Debug, Deserialize)]
+    struct Programme {
+        #[serde(rename = "@start")]
+        start: String,
+        #[serde(rename = "@event_id")]
+        event_id: u32,
+        #[serde(rename = "@duration")]
+        duration: Option<u32>,
+        title: String,
+        #[serde(rename = "title/@lang")]
+        title_lang: Option<String>,
+        category: Vec<String>,
+        desc: Option<String>,
+    }
+
+    fn read_programmes() -> Vec<Vec<OutPutTagValue>> {
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(PathBuf::from(XML_FILE)).unwrap(),
+        ));
+        let programme = string_vec(vec!["tv", "programme"]);
+        let mut config = XmlReadConfig::new(programme.clone());
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                programme.clone(),
+                HashSet::from_iter(vec![
+                    TagValueName::new_attribute("start".to_string()),
+                    TagValueName::new_attribute("event_id".to_string()),
+                ]),
+            ))
+            .unwrap();
+        for (child, values) in [
+            (
+                "title",
+                vec![
+                    TagValueName::new_tag_text(),
+                    TagValueName::new_attribute("lang".to_string()),
+                ],
+            ),
+            ("category", vec![TagValueName::new_tag_text()]),
+        ] {
+            let mut tag_hierarchy = programme.clone();
+            tag_hierarchy.push(child.to_string());
+            config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy,
+                    HashSet::from_iter(values),
+                ))
+                .unwrap();
+        }
+        read_xml(&mut reader, &config).unwrap()
+    }
+
+    #[test_log::test]
+    fn test_from_groups_programme() {
+        let programmes: Vec<Programme> = from_groups(&read_programmes()).unwrap();
+        assert_eq!(programmes.len(), 2);
+        assert_eq!(programmes[0].start, "20241123090000 +0900");
+        assert_eq!(programmes[0].event_id, 32665);
+        assert_eq!(programmes[1].event_id, 32666);
+        assert_eq!(programmes[0].duration, None);
+        assert_eq!(programmes[0].title, "TITLE_1_1");
+        assert_eq!(programmes[0].title_lang.as_deref(), Some("ja_JP"));
+        assert_eq!(programmes[0].category, vec!["CAT_1", "CAT_2"]);
+        assert_eq!(programmes[0].desc, None);
+    }
+
+    #[test_log::test]
+    fn test_from_group_error_names_field() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct SingleCategory {
+            category: String,
+        }
+        let groups = read_programmes();
+        let err = from_group::<SingleCategory>(&groups[0]).unwrap_err();
+        assert!(err.to_string().contains("\"category\""), "{}", err);
+    }
+
+    #[test_log::test]
+    fn test_from_group_missing_field_default() {
+        fn default_rating() -> u32 {
+            3
+        }
+        #[derive(Debug, Deserialize)]
+        struct WithDefaults {
+            title: String,
+            #[serde(rename = "@duration", default)]
+            duration: u32,
+            #[serde(default = "default_rating")]
+            rating: u32,
+            #[serde(default)]
+            desc: Vec<String>,
+        }
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct WithoutDefault {
+            title: String,
+            desc: Vec<String>,
+        }
+        let groups = read_programmes();
+        let with_defaults = from_group::<WithDefaults>(&groups[0]).unwrap();
+        assert_eq!(with_defaults.title, "TITLE_1_1");
+        assert_eq!(with_defaults.duration, 0);
+        assert_eq!(with_defaults.rating, 3);
+        assert!(with_defaults.desc.is_empty());
+
+        // `#[serde(default)]`のない`Vec`のフィールドに値がない場合はエラー
+        let err = from_group::<WithoutDefault>(&groups[0]).unwrap_err();
+        assert!(err.to_string().contains("desc"), "{}", err);
+    }
+}
@@ -11,9 +11,6 @@ impl TagHierarchyStore {
             tag_store: HashMap::new(),
         }
     }
-    pub(in crate::reader) fn get(&self, key: &Vec<String>) -> Option<&OutPutTagValue> {
-        self.tag_store.get(key)
-    }
     pub(in crate::reader) fn remove(&mut self, key: &Vec<String>) -> Option<OutPutTagValue> {
         self.tag_store.remove(key)
     }
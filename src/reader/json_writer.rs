@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use serde_json::{Map, Value};
+
+use super::output_tag_value::OutPutTagValue;
+use super::tag_value_type::TagValueType;
+use super::xml_read_config::XmlReadConfig;
+use super::xml_reader::read_xml_each;
+
+/// テキストを格納するキー
+pub const TEXT_KEY: &str = "#text";
+/// 属性名の前に付ける文字
+pub const ATTRIBUTE_PREFIX: &str = "@";
+/// タグの内側のXMLを格納するキー
+pub const INNER_XML_KEY: &str = "#inner_xml";
+/// タグ全体のXMLを格納するキー
+pub const OUTER_XML_KEY: &str = "#outer_xml";
+/// コメントを格納するキー(値は出現順の配列)
+pub const COMMENT_KEY: &str = "#comment";
+/// 処理命令のターゲット名の前に付ける文字(値は出現順の配列)
+pub const PROCESSING_INSTRUCTION_PREFIX: &str = "?";
+
+/// 1グループ分の`OutPutTagValue`を、タグの入れ子構造を持つJSONに変換します。
+///
+/// 出力形式は以下の通りです。
+///
+/// ```json
+/// {
+///   "programme": {
+///     "@start": "20241123090000 +0900",
+///     "category": [{ "#text": "CAT_1", "@lang": "ja_JP" }, { "#text": "CAT_2", "@lang": "en" }],
+///     "title": [{ "#text": "TITLE_1_1" }],
+///     "video": [{ "aspect": [{ "#text": "16:9" }] }]
+///   }
+/// }
+/// ```
+///
+/// - グループのタグ名をキーとするオブジェクトを1つ持つオブジェクトになります。
+/// - タグはオブジェクトで表し、属性は`@属性名`、テキストは`#text`をキーとする文字列になります。
+///   (1つのタグにテキストが複数ある場合は連結します)
+/// - 子タグはタグ名をキーとする配列になります。子タグが1つの場合も配列です。
+/// - 設定されていない途中のタグ階層は、値を持たないオブジェクトとして補います。
+///   (同じ親の下では1つのオブジェクトにまとめられます)
+/// - キーは名前順に並びます。
+pub fn group_to_json(group: &[OutPutTagValue]) -> Value {
+    // 子タグは親タグより先に出力されるため、完成したタグを積んでおき、親タグが来たら取り出す
+    let mut stack: Vec<(Vec<String>, Map<String, Value>)> = Vec::new();
+    for out_put_tag_value in group {
+        let tag_hierarchy = out_put_tag_value.get_tag_hierarchy();
+        let mut node = tag_values_to_json(out_put_tag_value);
+        let first_descendant = stack
+            .iter()
+            .rposition(|(x, _)| !is_descendant(x, &tag_hierarchy))
+            .map_or(0, |i| i + 1);
+        for (descendant_hierarchy, descendant) in stack.drain(first_descendant..) {
+            put_child(
+                &mut node,
+                &descendant_hierarchy[tag_hierarchy.len()..],
+                descendant,
+            );
+        }
+        stack.push((tag_hierarchy, node));
+    }
+
+    let Some((root_hierarchy, mut root)) = stack.pop() else {
+        return Value::Object(Map::new());
+    };
+    // 通常は残らないが、残ったものは基準のタグ階層からの相対パスで追加する
+    for (tag_hierarchy, node) in stack {
+        let common = tag_hierarchy
+            .iter()
+            .zip(root_hierarchy.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        if common == root_hierarchy.len() {
+            put_child(&mut root, &tag_hierarchy[common..], node);
+        }
+    }
+    let mut group_json = Map::new();
+    group_json.insert(
+        root_hierarchy.last().cloned().unwrap_or_default(),
+        Value::Object(root),
+    );
+    Value::Object(group_json)
+}
+
+fn is_descendant(tag_hierarchy: &[String], ancestor: &[String]) -> bool {
+    tag_hierarchy.len() > ancestor.len() && tag_hierarchy.starts_with(ancestor)
+}
+
+fn tag_values_to_json(out_put_tag_value: &OutPutTagValue) -> Map<String, Value> {
+    let mut node = Map::new();
+    let mut text: Option<String> = None;
+    for tag_value in out_put_tag_value.get_tag_values() {
+        match tag_value.get_value_type() {
+            TagValueType::TagText => text
+                .get_or_insert_with(String::new)
+                .push_str(tag_value.get_value()),
+            TagValueType::AttributeValue => {
+                node.insert(
+                    format!("{}{}", ATTRIBUTE_PREFIX, tag_value.get_tag_name()),
+                    Value::String(tag_value.get_value().to_string()),
+                );
+            }
+            TagValueType::InnerXml => {
+                node.insert(
+                    INNER_XML_KEY.to_string(),
+                    Value::String(tag_value.get_value().to_string()),
+                );
+            }
+            TagValueType::OuterXml => {
+                node.insert(
+                    OUTER_XML_KEY.to_string(),
+                    Value::String(tag_value.get_value().to_string()),
+                );
+            }
+            TagValueType::Comment => {
+                push_to_array(&mut node, COMMENT_KEY.to_string(), tag_value.get_value())
+            }
+            TagValueType::ProcessingInstruction => push_to_array(
+                &mut node,
+                format!(
+                    "{}{}",
+                    PROCESSING_INSTRUCTION_PREFIX,
+                    tag_value.get_tag_name()
+                ),
+                tag_value.get_value(),
+            ),
+        }
+    }
+    if let Some(text) = text {
+        node.insert(TEXT_KEY.to_string(), Value::String(text));
+    }
+    node
+}
+
+fn push_to_array(node: &mut Map<String, Value>, key: String, value: &str) {
+    if let Value::Array(values) = node.entry(key).or_insert_with(|| Value::Array(Vec::new())) {
+        values.push(Value::String(value.to_string()));
+    }
+}
+
+fn put_child(node: &mut Map<String, Value>, relative_path: &[String], child: Map<String, Value>) {
+    let Some((name, ancestors)) = relative_path.split_last() else {
+        return;
+    };
+    let mut parent = node;
+    for ancestor in ancestors {
+        let siblings = parent
+            .entry(ancestor.clone())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        let Value::Array(siblings) = siblings else {
+            return;
+        };
+        if siblings.is_empty() {
+            siblings.push(Value::Object(Map::new()));
+        }
+        let Some(Value::Object(last)) = siblings.last_mut() else {
+            return;
+        };
+        parent = last;
+    }
+    if let Value::Array(siblings) = parent
+        .entry(name.clone())
+        .or_insert_with(|| Value::Array(Vec::new()))
+    {
+        siblings.push(Value::Object(child));
+    }
+}
+
+/// 1グループ分の`OutPutTagValue`を、NDJSONの1行(改行付き)として書き出します。
+///
+/// 各行の形式は`group_to_json`と同じです。
+pub fn write_ndjson_group<W: Write>(
+    writer: &mut W,
+    group: &[OutPutTagValue],
+) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer(&mut *writer, &group_to_json(group))?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// XMLを順次読み込み、1グループを1行としてNDJSONで書き出します。
+///
+/// # 戻り値
+/// - 書き出したグループの数
+pub fn write_xml_as_ndjson<R: BufRead, W: Write>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    writer: &mut W,
+) -> Result<usize, Box<dyn Error>> {
+    let mut count = 0;
+    read_xml_each(reader, read_config, |group| {
+        count += 1;
+        write_ndjson_group(writer, &group)
+    })?;
+    writer.flush()?;
+    Ok(count)
+}
+
+/// XMLを順次読み込み、すべてのグループをJSONの配列として書き出します。
+///
+/// 配列の要素の形式は`group_to_json`と同じです。
+///
+/// # 戻り値
+/// - 書き出したグループの数
+pub fn write_xml_as_json<R: BufRead, W: Write>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    writer: &mut W,
+) -> Result<usize, Box<dyn Error>> {
+    let mut count = 0;
+    writer.write_all(b"[")?;
+    read_xml_each(reader, read_config, |group| {
+        if count > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(b"\n")?;
+        serde_json::to_writer(&mut *writer, &group_to_json(&group))?;
+        count += 1;
+        Ok(())
+    })?;
+    writer.write_all(b"\n]\n")?;
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::reader::{
+        util::string_vec,
+        xml_read_config::{TagValueName, XmlTagReadConfig},
+    };
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    fn programme_config() -> XmlReadConfig {
+        let programme = string_vec(vec!["tv", "programme"]);
+        let mut config = XmlReadConfig::new(programme.clone());
+        for (tag_hierarchy, values) in [
+            (
+                programme,
+                vec![TagValueName::new_attribute("event_id".to_string())],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "category"]),
+                vec![
+                    TagValueName::new_tag_text(),
+                    TagValueName::new_attribute("lang".to_string()),
+                ],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "video", "aspect"]),
+                vec![TagValueName::new_tag_text()],
+            ),
+        ] {
+            config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy,
+                    HashSet::from_iter(values),
+                ))
+                .unwrap();
+        }
+        config
+    }
+
+    #[test_log::test]
+    fn test_write_xml_as_ndjson() {
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let mut out = Vec::new();
+        let count = write_xml_as_ndjson(&mut reader, &programme_config(), &mut out).unwrap();
+        assert_eq!(count, 2);
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(
+            first,
+            json!({
+                "programme": {
+                    "@event_id": "32665",
+                    "category": [
+                        { "#text": "CAT_1", "@lang": "ja_JP" },
+                        { "#text": "CAT_2", "@lang": "en" }
+                    ],
+                    "video": [{ "aspect": [{ "#text": "16:9" }] }]
+                }
+            })
+        );
+    }
+
+    #[test_log::test]
+    fn test_write_xml_as_json() {
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let mut out = Vec::new();
+        write_xml_as_json(&mut reader, &programme_config(), &mut out).unwrap();
+        let value: Value = serde_json::from_slice(&out).unwrap();
+        let groups = value.as_array().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1]["programme"]["@event_id"], "32666");
+    }
+}
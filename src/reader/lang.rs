@@ -0,0 +1,186 @@
+use super::output_tag_value::OutPutTagValue;
+
+/// 言語付きのテキストです。(`<title lang="ja_JP">`など)
+///
+/// 言語は、タグ自身の`xml:lang`属性、`lang`属性、祖先のタグの`xml:lang`属性の順に決まります。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LangText {
+    /// テキスト
+    value: String,
+    /// 言語
+    lang: Option<String>,
+}
+
+impl LangText {
+    /// 新しい`LangText`を作成します。
+    pub fn new(value: String, lang: Option<String>) -> Self {
+        LangText { value, lang }
+    }
+
+    /// テキストを取得します。
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// 言語を取得します。
+    pub fn get_lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    /// `OutPutTagValue`のテキストと言語から`LangText`を作成します。(テキストがない場合は`None`)
+    pub fn from_out_put_tag_value(out_put_tag_value: &OutPutTagValue) -> Option<Self> {
+        out_put_tag_value.get_text().map(|text| {
+            LangText::new(
+                text.to_string(),
+                out_put_tag_value.get_lang().map(|x| x.to_string()),
+            )
+        })
+    }
+}
+
+/// 2つの言語が一致するかどうかを確認します。
+///
+/// 大文字・小文字と区切り文字(`_`、`-`)の違いは無視します。(`ja_JP`と`ja-jp`は一致)
+fn is_same_lang(a: &str, b: &str) -> bool {
+    let normalize = |x: &str| x.trim().replace('_', "-").to_ascii_lowercase();
+    normalize(a) == normalize(b)
+}
+
+/// 言語の主タグ(`ja_JP`であれば`ja`)を取得します。
+fn primary_lang(lang: &str) -> String {
+    lang.trim()
+        .split(['_', '-'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// 優先する言語の順に、テキストを1つ選びます。
+///
+/// `preferred_langs`の先頭から順に、以下のテキストを探します。
+/// 1. 言語が一致するテキスト
+/// 2. 主タグが一致するテキスト(`en_US`に対する`en`、`ja`に対する`ja_JP`など)
+///
+/// いずれの言語にも一致しない場合は、最初のテキストを返します。
+///
+/// # 引数
+/// - `lang_texts`: 選択の対象
+/// - `preferred_langs`: 優先する言語のリスト(例: `&["ja_JP", "en"]`。空の場合は最初のテキスト)
+pub fn select_lang_text<'a>(
+    lang_texts: &'a [LangText],
+    preferred_langs: &[&str],
+) -> Option<&'a LangText> {
+    for preferred_lang in preferred_langs {
+        let found = lang_texts
+            .iter()
+            .find(|x| {
+                x.get_lang()
+                    .is_some_and(|lang| is_same_lang(lang, preferred_lang))
+            })
+            .or_else(|| {
+                let preferred_primary = primary_lang(preferred_lang);
+                lang_texts.iter().find(|x| {
+                    x.get_lang()
+                        .is_some_and(|lang| primary_lang(lang) == preferred_primary)
+                })
+            });
+        if found.is_some() {
+            return found;
+        }
+    }
+    lang_texts.first()
+}
+
+/// グループから、指定したタグ階層のテキストを言語付きで出現順に取得します。
+pub fn pick_up_lang_texts(group: &[OutPutTagValue], tag_hierarchy: &[String]) -> Vec<LangText> {
+    group
+        .iter()
+        .filter(|x| x.get_tag_hierarchy() == tag_hierarchy)
+        .filter_map(LangText::from_out_put_tag_value)
+        .collect()
+}
+
+/// グループから、指定したタグ階層のテキストを優先する言語の順に1つ選んで取得します。
+///
+/// 選択の規則は`select_lang_text`と同じです。
+pub fn pick_up_text_by_lang(
+    group: &[OutPutTagValue],
+    tag_hierarchy: &[String],
+    preferred_langs: &[&str],
+) -> Option<String> {
+    let lang_texts = pick_up_lang_texts(group, tag_hierarchy);
+    select_lang_text(&lang_texts, preferred_langs).map(|x| x.get_value().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::reader::util::string_vec;
+    use crate::reader::xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig};
+    use crate::reader::xml_reader::read_xml;
+
+    fn lang_text(value: &str, lang: Option<&str>) -> LangText {
+        LangText::new(value.to_string(), lang.map(|x| x.to_string()))
+    }
+
+    #[test_log::test]
+    fn test_select_lang_text() {
+        let titles = vec![
+            lang_text("Title", Some("en")),
+            lang_text("タイトル", Some("ja_JP")),
+            lang_text("Titel", Some("de-DE")),
+        ];
+        let select = |preferred: &[&str]| select_lang_text(&titles, preferred).unwrap().get_value();
+        assert_eq!(select(&["ja_JP", "en"]), "タイトル");
+        assert_eq!(select(&["fr", "ja-jp"]), "タイトル");
+        assert_eq!(select(&["de"]), "Titel");
+        assert_eq!(select(&["fr"]), "Title");
+        assert_eq!(select(&[]), "Title");
+        assert!(select_lang_text(&[], &["en"]).is_none());
+    }
+
+    #[test_log::test]
+    fn test_inherited_xml_lang() {
+        let programme = string_vec(vec!["tv", "programme"]);
+        let title = string_vec(vec!["tv", "programme", "title"]);
+        let mut config = XmlReadConfig::new(programme.clone());
+        for tag_hierarchy in [&programme, &title] {
+            config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy.clone(),
+                    HashSet::from_iter(vec![TagValueName::new_tag_text()]),
+                ))
+                .unwrap();
+        }
+        let mut reader = quick_xml::Reader::from_str(
+            r#"<tv xml:lang="de">
+                <programme xml:lang="fr">
+                  <title>Le titre</title>
+                  <title lang="en">The title</title>
+                  <title xml:lang="ja" lang="en">題名</title>
+                </programme>
+                <programme><title>Der Titel</title></programme>
+            </tv>"#,
+        );
+        let groups = read_xml(&mut reader, &config).unwrap();
+        assert_eq!(
+            pick_up_lang_texts(&groups[0], &title),
+            vec![
+                lang_text("Le titre", Some("fr")),
+                lang_text("The title", Some("en")),
+                lang_text("題名", Some("ja")),
+            ]
+        );
+        assert_eq!(
+            pick_up_text_by_lang(&groups[0], &title, &["en_US", "fr"]).as_deref(),
+            Some("The title")
+        );
+        assert_eq!(
+            pick_up_lang_texts(&groups[1], &title),
+            vec![lang_text("Der Titel", Some("de"))]
+        );
+    }
+}
@@ -1,23 +1,54 @@
-use std::collections::HashMap;
-use std::{collections::HashSet, error::Error};
+use std::collections::BTreeMap;
+use std::error::Error;
 
-use log::{debug, warn};
+use log::warn;
 use quick_xml::events::BytesStart;
 
+use super::lang::LangText;
 use super::tag_value::TagValue;
-use super::tag_value_type::TagValueType::{AttributeValue, TagText};
+use super::tag_value_type::TagValueType::{self, AttributeValue, TagText};
+use super::typed_value::{TypedValue, ValueConversionError, ValueKind};
 use super::util::get_last_vec_element;
-use super::xml_read_config::XmlTagReadConfig;
+use super::xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig};
 
 /// `OutPutValue` は、XML のタグや属性の情報を格納する構造体です。
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OutPutTagValue {
     /// タグ階層
     tag_hierarchy: Vec<String>,
     /// 値のリスト
     tag_values: Vec<TagValue>,
+    /// 言語（タグ自身の`xml:lang`属性、`lang`属性、祖先のタグの`xml:lang`属性の順に決まる）
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    lang: Option<String>,
+    /// 文書内での開始タグの順番（0から始まる）
+    document_order: usize,
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か（0から始まる）
+    sibling_index: usize,
+    /// 何番目のグループに含まれるか（0から始まる）
+    group_index: usize,
 }
 impl OutPutTagValue {
+    /// 値をすべて指定して`OutPutTagValue`を作成します。(`OutPutTagValueRef::into_owned`で使用)
+    pub(in crate::reader) fn from_parts(
+        tag_hierarchy: Vec<String>,
+        tag_values: Vec<TagValue>,
+        lang: Option<String>,
+        document_order: usize,
+        sibling_index: usize,
+        group_index: usize,
+    ) -> Self {
+        OutPutTagValue {
+            tag_hierarchy,
+            tag_values,
+            lang,
+            document_order,
+            sibling_index,
+            group_index,
+        }
+    }
+
     /// タグ名(タグ階層の最後のタグ名)を取得します。
     pub fn get_tag_name(&self) -> Result<String, Box<dyn Error>> {
         get_last_vec_element(&self.tag_hierarchy)
@@ -40,6 +71,154 @@ impl OutPutTagValue {
         self.tag_values.clone()
     }
 
+    /// タグのテキストを取得します。(テキストを取得していない場合は`None`)
+    pub fn get_text(&self) -> Option<&str> {
+        self.tag_values
+            .iter()
+            .find(|x| *x.get_value_type() == TagText)
+            .map(|x| x.get_value())
+    }
+
+    /// 属性の値を取得します。(属性を取得していない場合は`None`)
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.tag_values
+            .iter()
+            .find(|x| *x.get_value_type() == AttributeValue && x.get_tag_name() == name)
+            .map(|x| x.get_value())
+    }
+
+    /// 取得した属性を、属性名をキーとするマップで取得します。
+    pub fn attributes(&self) -> BTreeMap<&str, &str> {
+        self.tag_values
+            .iter()
+            .filter(|x| *x.get_value_type() == AttributeValue)
+            .map(|x| (x.get_tag_name(), x.get_value()))
+            .collect()
+    }
+
+    /// `TagValueName`に該当する値を取得します。(複数ある場合は最初の値)
+    pub fn get_value(&self, tag_value_name: &TagValueName) -> Option<&str> {
+        self.get_values(tag_value_name).next()
+    }
+
+    /// `TagValueName`に該当する値を、すべて取得します。
+    pub fn get_values<'a>(
+        &'a self,
+        tag_value_name: &TagValueName,
+    ) -> impl Iterator<Item = &'a str> {
+        self.tag_values
+            .iter()
+            .filter(|x| tag_value_name.is_match(x))
+            .map(|x| x.get_value())
+    }
+
+    /// `TagValueName`に該当する値を、指定した種類に変換して取得します。
+    ///
+    /// # 戻り値
+    /// - `Ok(Some(TypedValue))`: 値があり、変換できた場合
+    /// - `Ok(None)`: 値がない場合
+    /// - `Err(ValueConversionError)`: 変換できなかった場合、タグ階層と値を含む
+    pub fn get_value_as(
+        &self,
+        tag_value_name: &TagValueName,
+        value_kind: &ValueKind,
+    ) -> Result<Option<TypedValue>, ValueConversionError> {
+        self.tag_values
+            .iter()
+            .find(|x| tag_value_name.is_match(x))
+            .map(|x| {
+                x.get_typed_value(value_kind)
+                    .map_err(|e| e.with_tag_hierarchy(&self.tag_hierarchy))
+            })
+            .transpose()
+    }
+
+    /// タグのテキストを、指定した種類に変換して取得します。(`get_value_as`を参照)
+    pub fn get_text_as(
+        &self,
+        value_kind: &ValueKind,
+    ) -> Result<Option<TypedValue>, ValueConversionError> {
+        self.get_value_as(&TagValueName::new_tag_text(), value_kind)
+    }
+
+    /// 属性の値を、指定した種類に変換して取得します。(`get_value_as`を参照)
+    pub fn get_attribute_as(
+        &self,
+        name: &str,
+        value_kind: &ValueKind,
+    ) -> Result<Option<TypedValue>, ValueConversionError> {
+        self.get_value_as(&TagValueName::new_attribute(name.to_string()), value_kind)
+    }
+
+    /// 言語を取得します。
+    ///
+    /// タグ自身の`xml:lang`属性、`lang`属性、祖先のタグの`xml:lang`属性の順に決まります。
+    /// (読み取り設定で`lang`属性を指定していなくても取得できます)
+    pub fn get_lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    /// テキストを言語付きで取得します。(テキストがない場合は`None`)
+    pub fn get_lang_text(&self) -> Option<LangText> {
+        LangText::from_out_put_tag_value(self)
+    }
+
+    /// 文書内での開始タグの順番（0から始まる）を取得します。
+    ///
+    /// `read_xml`の結果は終了タグの順（子タグが親タグより先）に並ぶため、
+    /// 文書の順に並べ替える場合に使用します。
+    pub fn get_document_order(&self) -> usize {
+        self.document_order
+    }
+
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か（0から始まる）を取得します。
+    ///
+    /// 設定で指定していない兄弟タグも数えるため、値を絞り込んだ後でも
+    /// 同じ`category`や`item`の何番目であるかがわかります。
+    pub fn get_sibling_index(&self) -> usize {
+        self.sibling_index
+    }
+
+    /// 何番目のグループに含まれるか（0から始まる）を取得します。
+    ///
+    /// `read_xml`の結果のインデックス、`read_xml_each`で`on_group`が呼び出された順番と同じです。
+    pub fn get_group_index(&self) -> usize {
+        self.group_index
+    }
+
+    /// 文書内での開始タグの順番を設定します。
+    pub(in crate::reader) fn set_document_order(&mut self, document_order: usize) {
+        self.document_order = document_order;
+    }
+
+    /// 兄弟タグの中での順番を設定します。
+    pub(in crate::reader) fn set_sibling_index(&mut self, sibling_index: usize) {
+        self.sibling_index = sibling_index;
+    }
+
+    /// グループの順番を設定します。
+    pub(in crate::reader) fn set_group_index(&mut self, group_index: usize) {
+        self.group_index = group_index;
+    }
+
+    /// 言語を設定します。
+    pub(in crate::reader) fn set_lang(&mut self, lang: Option<String>) {
+        self.lang = lang;
+    }
+
+    /// タグの値のリストを、読み取り設定で宣言された種類に変換して取得します。
+    /// 順序は`get_tag_values`と同じです。
+    /// 変換できない値がある場合は、タグ階層と値を含むエラーを返します。
+    pub fn get_typed_values(
+        &self,
+        read_config: &XmlReadConfig,
+    ) -> Result<Vec<TypedValue>, ValueConversionError> {
+        self.tag_values
+            .iter()
+            .map(|tag_value| read_config.convert_tag_value(&self.tag_hierarchy, tag_value))
+            .collect()
+    }
+
     /// タグ階層を設定します。
     /// タグ階層が空の場合のみ設定します。
     /// タグ階層が空でない場合は、現在のタグ階層と新しいタグ階層を比較します。
@@ -49,11 +228,10 @@ impl OutPutTagValue {
         &mut self,
         tag_hitrarchy: &Vec<String>,
     ) -> Result<(), Box<dyn Error>> {
-        let tag_hitrarchy_clone = tag_hitrarchy.clone();
         if self.tag_hierarchy.is_empty() {
-            self.tag_hierarchy = tag_hitrarchy_clone;
+            self.tag_hierarchy = tag_hitrarchy.clone();
             Ok(())
-        } else if self.tag_hierarchy != tag_hitrarchy_clone {
+        } else if self.tag_hierarchy != *tag_hitrarchy {
             let s_err = format!(
                 "
                 TAG HIERARCHY IS DIFFERENT. tag_hitrarchy {:?} != self.tag_hitrarchy {:?}",
@@ -80,12 +258,8 @@ impl OutPutTagValue {
     ) -> Result<(), Box<dyn Error>> {
         {
             self.set_tag_hierarchy(tag_hierarchy)?;
-            // 重複を避けるために HashSet を使用
-            let mut unique_values: HashSet<TagValue> = HashSet::new();
-            for val in &self.tag_values {
-                unique_values.insert(val.clone());
-            }
-            if !unique_values.contains(&value) {
+            // 値の数は少ないため、複製せずに線形探索で重複を確認する
+            if !self.tag_values.contains(&value) {
                 self.tag_values.push(value);
             }
             Ok(())
@@ -102,50 +276,42 @@ impl OutPutTagValue {
             TagValue::new(TagText, tag_name_from_tag_hierarchy, text_value);
         self.put_tag_value(tag_hierarchy, out_put_text_event_value)
     }
-    /*
-              pub(in crate::pick_up_xml_value
-    ::reader::common)  fn put_all_tag_attribute_from_start_tag(
-                &mut self,
-                tag_hierarchy: &Vec<String>,
-                start: &BytesStart,
-            ) -> Result<(), Box<dyn Error>> {
-                let tag_name_from_start_tag = String::from_utf8(start.name().as_ref().to_vec())?;
-                let tag_name_from_tag_hierarchy = self.get_tag_name()?;
-                if tag_name_from_tag_hierarchy != tag_name_from_start_tag {
-                    let s_err = format!(
-                        "TAG NAME IS DIFFERENT. tag_hierarchy {:?} , tag_name_from_start_tag {:?} != tag_name_from_tag_hierarchy {:?}",
-                        self.tag_hierarchy, tag_name_from_start_tag, tag_name_from_tag_hierarchy
-                    );
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        s_err,
-                    )));
-                }
-                for attr in start.attributes().flatten() {
-                    let attr_name = match String::from_utf8(attr.key.as_ref().to_vec()) {
-                        Ok(name) => name,
-                        Err(e) => {
-                            warn!("FAILED TO CONVERT ATTRIBUTE NAME TO STRING: {}", e);
-                            continue;
-                        }
-                    };
-                    let attr_value = match String::from_utf8(attr.value.to_vec()) {
-                        Ok(value) => value,
-                        Err(e) => {
-                            warn!("FAILED TO CONVERT ATTRIBUTE VALUE TO STRING: {}", e);
-                            continue;
-                        }
-                    };
-                    let out_put_attrubute_value = TagValue::new(AttributeValue, attr_name, attr_value);
-                    self.put_tag_value(tag_hierarchy, out_put_attrubute_value)?;
-                }
-                Ok(())
-            }
-        */
+    /// タグのXMLそのもの(`InnerXml`または`OuterXml`)を追加します。
+    pub(in crate::reader) fn put_raw_xml_value(
+        &mut self,
+        tag_hierarchy: &Vec<String>,
+        value_type: TagValueType,
+        raw_xml: String,
+    ) -> Result<(), Box<dyn Error>> {
+        let tag_name_from_tag_hierarchy = self.get_tag_name()?;
+        let out_put_raw_xml_value = TagValue::new(value_type, tag_name_from_tag_hierarchy, raw_xml);
+        self.put_tag_value(tag_hierarchy, out_put_raw_xml_value)
+    }
+
+    /// コメントまたは処理命令を追加します。(同じ内容のものが複数ある場合も、すべて追加する)
+    ///
+    /// # 引数
+    /// - `name`: 処理命令の場合はターゲット名(コメントの場合は無視し、タグ名を使用する)
+    pub(in crate::reader) fn put_comment_or_processing_instruction(
+        &mut self,
+        tag_hierarchy: &Vec<String>,
+        value_type: TagValueType,
+        name: String,
+        value: String,
+    ) -> Result<(), Box<dyn Error>> {
+        self.set_tag_hierarchy(tag_hierarchy)?;
+        let name = match value_type {
+            TagValueType::ProcessingInstruction => name,
+            _ => self.get_tag_name()?,
+        };
+        self.tag_values.push(TagValue::new(value_type, name, value));
+        Ok(())
+    }
+
     pub(in crate::reader) fn put_selected_tag_attribute_from_start_tag(
         &mut self,
         tag_hierarchy: &Vec<String>,
-        read_config: &HashMap<Vec<String>, XmlTagReadConfig>,
+        config: &XmlTagReadConfig,
         start: &BytesStart,
     ) -> Result<(), Box<dyn Error>> {
         let tag_name_from_start_tag = String::from_utf8(start.name().as_ref().to_vec())?;
@@ -170,32 +336,117 @@ impl OutPutTagValue {
                     continue;
                 }
             };
-            let config_o: Option<&XmlTagReadConfig> = read_config.get(tag_hierarchy);
-            let config = match config_o {
-                Some(v) => v,
-                None => {
-                    debug!("ATTRIBUTE LIST NOT FOUND.");
+            if !config.is_target_attribute(&attr_name) {
+                continue;
+            }
+            let attr_value = match String::from_utf8(attr.value.to_vec()) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("FAILED TO CONVERT ATTRIBUTE VALUE TO STRING: {}", e);
                     continue;
                 }
             };
-            let attribute_list = config.get_target_tag_value_names();
-            for attr_value_name in attribute_list {
-                if (attr_value_name.get_value_type() == AttributeValue)
-                    && (attr_value_name.get_name() == attr_name)
-                {
-                    let attr_value = match String::from_utf8(attr.value.to_vec()) {
-                        Ok(value) => value,
-                        Err(e) => {
-                            warn!("FAILED TO CONVERT ATTRIBUTE VALUE TO STRING: {}", e);
-                            continue;
-                        }
-                    };
-                    let out_put_attrubute_value =
-                        TagValue::new(AttributeValue, attr_name.clone(), attr_value);
-                    self.put_tag_value(tag_hierarchy, out_put_attrubute_value)?;
-                }
-            }
+            let out_put_attrubute_value = TagValue::new(AttributeValue, attr_name, attr_value);
+            self.put_tag_value(tag_hierarchy, out_put_attrubute_value)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::xml_read_config::AttributeNamePattern;
+    use crate::reader::xml_reader::read_xml;
+
+    #[test_log::test]
+    fn test_text_and_attribute_accessors() {
+        let config = build_read_config(
+            "tv/programme",
+            vec![
+                ("", TagValueName::new_attribute("event_id".to_string())),
+                ("", TagValueName::new_attribute("channel".to_string())),
+                ("category", TagValueName::new_tag_text()),
+                ("category", TagValueName::new_attribute("lang".to_string())),
+            ],
+        );
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let groups = read_xml(&mut reader, &config).unwrap();
+        let category = &groups[0][1];
+        assert_eq!(category.get_text(), Some("CAT_2"));
+        assert_eq!(category.get_attribute("lang"), Some("en"));
+        assert_eq!(category.get_attribute("missing"), None);
+        assert_eq!(category.attributes(), BTreeMap::from([("lang", "en")]));
+
+        let programme = groups[0].last().unwrap();
+        assert_eq!(programme.get_text(), None);
+        assert_eq!(
+            programme
+                .get_attribute_as("event_id", &ValueKind::Integer)
+                .unwrap(),
+            Some(TypedValue::Integer(32665))
+        );
+        assert_eq!(programme.get_text_as(&ValueKind::Integer).unwrap(), None);
+        let err = programme
+            .get_attribute_as("channel", &ValueKind::Integer)
+            .unwrap_err();
+        assert!(err.to_string().contains("tv/programme/@channel"), "{}", err);
+    }
+
+    #[test_log::test]
+    fn test_all_attributes() {
+        let mut config =
+            build_read_config("tv/channel", vec![("", TagValueName::new_all_attributes())]);
+        let read_channel = |config: &XmlReadConfig| {
+            let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+            let groups = read_xml(&mut reader, config).unwrap();
+            groups[0].last().unwrap().clone()
+        };
+        let channel = read_channel(&config);
+        assert_eq!(
+            channel.attributes().into_keys().collect::<Vec<_>>(),
+            vec![
+                "id",
+                "original_network_id",
+                "service_id",
+                "tp",
+                "transport_stream_id"
+            ]
+        );
+        assert_eq!(
+            channel.get_value(&TagValueName::new_all_attributes()),
+            Some("GR2_1032")
+        );
+
+        let tag_hierarchy = config.get_tag_group_hierarchy();
+        let mut tag_read_config = config.get_tag_hierarchy_map()[&tag_hierarchy].clone();
+        tag_read_config
+            .set_excluded_attributes(["tp".to_string()].into())
+            .unwrap();
+        tag_read_config
+            .set_attribute_name_pattern(Some(AttributeNamePattern::new("_id$").unwrap()))
+            .unwrap();
+        tag_read_config
+            .set_value_kind(TagValueName::new_all_attributes(), ValueKind::Integer)
+            .unwrap();
+        config
+            .insert_xml_tag_read_config_to_hash_map(&tag_read_config)
+            .unwrap();
+        let channel = read_channel(&config);
+        assert_eq!(
+            channel.attributes(),
+            BTreeMap::from([
+                ("original_network_id", "32737"),
+                ("service_id", "1032"),
+                ("transport_stream_id", "32737"),
+            ])
+        );
+        assert!(
+            channel
+                .get_typed_values(&config)
+                .unwrap()
+                .contains(&TypedValue::Integer(1032))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::borrow::Cow;
+
+use super::output_tag_value::OutPutTagValue;
+use super::tag_value_ref::TagValueRef;
+use super::tag_value_type::TagValueType::{AttributeValue, TagText};
+use super::xml_read_config::TagValueName;
+
+/// `OutPutTagValue`の借用版です。(`read_xml_ref`で取得)
+///
+/// タグ階層は読み取り設定から、値は読み込んだXMLから借用します。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OutPutTagValueRef<'a> {
+    /// タグ階層
+    tag_hierarchy: &'a [String],
+    /// 値のリスト
+    tag_values: Vec<TagValueRef<'a>>,
+    /// 言語(`OutPutTagValue`と同じ)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    lang: Option<Cow<'a, str>>,
+    /// 文書内での開始タグの順番（0から始まる）
+    document_order: usize,
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か（0から始まる）
+    sibling_index: usize,
+    /// 何番目のグループに含まれるか（0から始まる）
+    group_index: usize,
+}
+
+impl<'a> OutPutTagValueRef<'a> {
+    pub(in crate::reader) fn new(tag_hierarchy: &'a [String]) -> Self {
+        OutPutTagValueRef {
+            tag_hierarchy,
+            tag_values: Vec::new(),
+            lang: None,
+            document_order: 0,
+            sibling_index: 0,
+            group_index: 0,
+        }
+    }
+
+    /// タグ名(タグ階層の最後のタグ名)を取得します。
+    pub fn get_tag_name(&self) -> &'a str {
+        self.tag_hierarchy
+            .last()
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// タグ階層を取得します。
+    pub fn get_tag_hierarchy(&self) -> &'a [String] {
+        self.tag_hierarchy
+    }
+
+    /// タグの値のリストを取得します。
+    pub fn get_tag_values(&self) -> &[TagValueRef<'a>] {
+        &self.tag_values
+    }
+
+    /// タグのテキストを取得します。(テキストを取得していない場合は`None`)
+    pub fn get_text(&self) -> Option<&str> {
+        self.tag_values
+            .iter()
+            .find(|x| *x.get_value_type() == TagText)
+            .map(|x| x.get_value())
+    }
+
+    /// 属性の値を取得します。(属性を取得していない場合は`None`)
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.tag_values
+            .iter()
+            .find(|x| *x.get_value_type() == AttributeValue && x.get_tag_name() == name)
+            .map(|x| x.get_value())
+    }
+
+    /// `TagValueName`に該当する値を取得します。
+    pub fn get_value(&self, tag_value_name: &TagValueName) -> Option<&str> {
+        self.tag_values
+            .iter()
+            .find(|x| tag_value_name.is_match_type_and_name(x.get_value_type(), x.get_tag_name()))
+            .map(|x| x.get_value())
+    }
+
+    /// 言語を取得します。(`OutPutTagValue::get_lang`を参照)
+    pub fn get_lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    /// 文書内での開始タグの順番（0から始まる）を取得します。
+    pub fn get_document_order(&self) -> usize {
+        self.document_order
+    }
+
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か（0から始まる）を取得します。
+    pub fn get_sibling_index(&self) -> usize {
+        self.sibling_index
+    }
+
+    /// 何番目のグループに含まれるか（0から始まる）を取得します。
+    pub fn get_group_index(&self) -> usize {
+        self.group_index
+    }
+
+    /// 所有する`OutPutTagValue`に変換します。
+    pub fn into_owned(self) -> OutPutTagValue {
+        OutPutTagValue::from_parts(
+            self.tag_hierarchy.to_vec(),
+            self.tag_values
+                .into_iter()
+                .map(TagValueRef::into_owned)
+                .collect(),
+            self.lang.map(Cow::into_owned),
+            self.document_order,
+            self.sibling_index,
+            self.group_index,
+        )
+    }
+
+    /// 開始タグの言語と順番を設定します。
+    pub(in crate::reader) fn set_start_tag(
+        &mut self,
+        lang: Option<Cow<'a, str>>,
+        document_order: usize,
+        sibling_index: usize,
+    ) {
+        self.lang = lang;
+        self.document_order = document_order;
+        self.sibling_index = sibling_index;
+    }
+
+    /// グループの順番を設定します。
+    pub(in crate::reader) fn set_group_index(&mut self, group_index: usize) {
+        self.group_index = group_index;
+    }
+
+    /// 値を追加します。(名前と値が同じ場合は追加しない)
+    pub(in crate::reader) fn put_tag_value(&mut self, tag_value: TagValueRef<'a>) {
+        if !self.tag_values.contains(&tag_value) {
+            self.tag_values.push(tag_value);
+        }
+    }
+
+    /// 値を追加します。(コメント・処理命令など、同じ内容のものが複数ある場合も、すべて追加する)
+    pub(in crate::reader) fn push_tag_value(&mut self, tag_value: TagValueRef<'a>) {
+        self.tag_values.push(tag_value);
+    }
+}
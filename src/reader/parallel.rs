@@ -0,0 +1,314 @@
+use std::error::Error;
+use std::io::{BufReader, Read};
+use std::ops::Range;
+
+use quick_xml::events::Event;
+use rayon::prelude::*;
+
+use super::output_tag_value::OutPutTagValue;
+use super::xml_read_config::XmlReadConfig;
+use super::xml_reader::read_xml_each;
+
+/// 1スレッドあたりのチャンクの数(チャンクごとの処理時間の偏りをならすため、スレッド数より多く分割する)
+const CHUNKS_PER_THREAD: usize = 4;
+
+/// 祖先のタグの、開始タグの範囲のリスト(外側のタグが先)です。
+type AncestorChain = Vec<Range<usize>>;
+
+/// グループのタグの位置です。(事前の走査で取得)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GroupSpan {
+    /// グループのタグ(開始タグから終了タグまで)の範囲
+    range: Range<usize>,
+    /// 祖先のタグ(`ancestor_chains`のインデックス)
+    ancestors: usize,
+    /// 文書内での開始タグの順番
+    document_order: usize,
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か
+    sibling_index: usize,
+}
+
+/// 並列に処理する単位です。(祖先のタグが同じ、連続したグループ)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Chunk {
+    /// 最初のグループ(`GroupSpan`のインデックス)
+    first_group: usize,
+    /// XMLの範囲(最初のグループの開始タグから、最後のグループの終了タグまで)
+    range: Range<usize>,
+}
+
+/// メモリ上のXMLを、グループの境界で分割してスレッドプールで読み込みます。(`parallel`フィーチャーが必要)
+///
+/// 結果は`read_xml`と同じく、文書の順に並びます。
+/// (`OutPutTagValue`の`document_order`・`sibling_index`・`group_index`も、文書全体での値になる)
+///
+/// 最初にXML全体を1回走査してグループのタグの範囲を求め、祖先のタグが同じ連続したグループを
+/// チャンクにまとめます。各チャンクは、祖先の開始タグ(`xml:lang`などの属性を含む)で囲んで読み込みます。
+/// 大きなファイルは、メモリマップ(`memmap2`クレートなど)を使用して渡すことができます。
+///
+/// # 引数
+/// - `xml`: XML全体(UTF-8)
+/// - `read_config`: 読み取り設定
+pub fn read_xml_parallel<X>(
+    xml: &X,
+    read_config: &XmlReadConfig,
+) -> Result<Vec<Vec<OutPutTagValue>>, Box<dyn Error>>
+where
+    X: AsRef<[u8]> + ?Sized,
+{
+    let chunk_count = rayon::current_num_threads() * CHUNKS_PER_THREAD;
+    read_xml_parallel_with_chunk_count(xml.as_ref(), read_config, chunk_count)
+}
+
+/// `read_xml_parallel`の本体です。(`chunk_count`はチャンクの数の目安)
+fn read_xml_parallel_with_chunk_count(
+    xml: &[u8],
+    read_config: &XmlReadConfig,
+    chunk_count: usize,
+) -> Result<Vec<Vec<OutPutTagValue>>, Box<dyn Error>> {
+    let tag_group_hierarchy = read_config.get_tag_group_hierarchy();
+    let (groups, ancestor_chains) = scan_groups(xml, &tag_group_hierarchy)?;
+    let chunks = split_into_chunks(&groups, chunk_count);
+
+    // `Box<dyn Error>`はスレッド間で受け渡せないため、エラーは文字列にする
+    let results: Vec<Result<Vec<Vec<OutPutTagValue>>, String>> = chunks
+        .par_iter()
+        .map(|chunk| {
+            let ancestors = &ancestor_chains[groups[chunk.first_group].ancestors];
+            read_chunk(xml, read_config, chunk, ancestors)
+                .map(|out_put_values| {
+                    rebase_chunk(
+                        out_put_values,
+                        &tag_group_hierarchy,
+                        chunk,
+                        &groups,
+                        ancestors.len(),
+                    )
+                })
+                .map_err(|e| format!("{} (bytes {:?})", e, chunk.range))
+        })
+        .collect();
+
+    let mut out_put_values = Vec::with_capacity(groups.len());
+    for result in results {
+        out_put_values.extend(result?);
+    }
+    Ok(out_put_values)
+}
+
+/// XML全体を走査して、グループのタグの範囲と祖先のタグを求めます。
+///
+/// # 戻り値
+/// - グループのタグの位置(文書の順)
+/// - 祖先のタグの開始タグの範囲のリスト(`GroupSpan::ancestors`で参照する)
+fn scan_groups(
+    xml: &[u8],
+    tag_group_hierarchy: &[String],
+) -> Result<(Vec<GroupSpan>, Vec<AncestorChain>), Box<dyn Error>> {
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    let group_depth = tag_group_hierarchy.len();
+    // 開いているタグの名前と、開始タグの範囲
+    let mut open_tags: Vec<(&[u8], Range<usize>)> = Vec::new();
+    let mut groups: Vec<GroupSpan> = Vec::new();
+    let mut ancestor_chains: Vec<AncestorChain> = Vec::new();
+    // 開いているグループのタグの、開始タグの位置と順番
+    let mut open_group: Option<(usize, usize, usize)> = None;
+    let mut document_order: usize = 0;
+
+    let is_group = |open_tags: &[(&[u8], Range<usize>)], name: &[u8]| -> bool {
+        open_tags.len() + 1 == group_depth
+            && open_tags
+                .iter()
+                .map(|(name, _)| *name)
+                .chain(std::iter::once(name))
+                .eq(tag_group_hierarchy.iter().map(|x| x.as_bytes()))
+    };
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader.read_event()?;
+        let end_position = reader.buffer_position() as usize;
+        match event {
+            Event::Eof => break,
+            Event::Start(ref start) | Event::Empty(ref start) => {
+                let name = &xml[position + 1..position + 1 + start.name().as_ref().len()];
+                if is_group(&open_tags, name) {
+                    // 祖先のタグが前のグループと異なれば、新しい祖先のタグのリストにする
+                    let ancestors = open_tags.iter().map(|(_, range)| range);
+                    let sibling_index = match (groups.last(), ancestor_chains.last()) {
+                        (Some(last), Some(chain)) if ancestors.clone().eq(chain.iter()) => {
+                            last.sibling_index + 1
+                        }
+                        _ => {
+                            ancestor_chains.push(ancestors.cloned().collect());
+                            0
+                        }
+                    };
+                    if matches!(event, Event::Empty(_)) {
+                        groups.push(GroupSpan {
+                            range: position..end_position,
+                            ancestors: ancestor_chains.len() - 1,
+                            document_order,
+                            sibling_index,
+                        });
+                    } else {
+                        open_group = Some((position, document_order, sibling_index));
+                    }
+                }
+                if matches!(event, Event::Start(_)) {
+                    open_tags.push((name, position..end_position));
+                }
+                document_order += 1;
+            }
+            Event::End(_) => {
+                open_tags.pop();
+                if open_tags.len() + 1 == group_depth
+                    && let Some((start, document_order, sibling_index)) = open_group.take()
+                {
+                    groups.push(GroupSpan {
+                        range: start..end_position,
+                        ancestors: ancestor_chains.len() - 1,
+                        document_order,
+                        sibling_index,
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok((groups, ancestor_chains))
+}
+
+/// グループを、祖先のタグが同じ連続したグループごとに、バイト数がおよそ等しいチャンクに分けます。
+fn split_into_chunks(groups: &[GroupSpan], chunk_count: usize) -> Vec<Chunk> {
+    let total_len: usize = groups.iter().map(|x| x.range.len()).sum();
+    let target_len = total_len.div_ceil(chunk_count.max(1)).max(1);
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut chunk_len = 0;
+    for (i, group) in groups.iter().enumerate() {
+        match chunks.last_mut() {
+            Some(chunk)
+                if chunk_len < target_len
+                    && groups[chunk.first_group].ancestors == group.ancestors =>
+            {
+                chunk.range.end = group.range.end;
+                chunk_len += group.range.len();
+            }
+            _ => {
+                chunks.push(Chunk {
+                    first_group: i,
+                    range: group.range.clone(),
+                });
+                chunk_len = group.range.len();
+            }
+        }
+    }
+    chunks
+}
+
+/// チャンクを、祖先の開始タグと終了タグで囲んで読み込みます。
+fn read_chunk(
+    xml: &[u8],
+    read_config: &XmlReadConfig,
+    chunk: &Chunk,
+    ancestors: &[Range<usize>],
+) -> Result<Vec<Vec<OutPutTagValue>>, Box<dyn Error>> {
+    let mut prefix: Vec<u8> = Vec::new();
+    let mut suffix: Vec<u8> = Vec::new();
+    for range in ancestors {
+        prefix.extend_from_slice(&xml[range.clone()]);
+    }
+    for range in ancestors.iter().rev() {
+        let start_tag = &xml[range.start + 1..range.end - 1];
+        let name_len = start_tag
+            .iter()
+            .position(|x| x.is_ascii_whitespace())
+            .unwrap_or(start_tag.len());
+        suffix.extend_from_slice(b"</");
+        suffix.extend_from_slice(&start_tag[..name_len]);
+        suffix.push(b'>');
+    }
+    let source = prefix
+        .as_slice()
+        .chain(&xml[chunk.range.clone()])
+        .chain(suffix.as_slice());
+    let mut reader = quick_xml::Reader::from_reader(BufReader::new(source));
+    let mut out_put_values: Vec<Vec<OutPutTagValue>> = Vec::new();
+    read_xml_each(&mut reader, read_config, |out_put_value| {
+        out_put_values.push(out_put_value);
+        Ok(())
+    })?;
+    Ok(out_put_values)
+}
+
+/// チャンク内での順番を、文書全体での順番に変換します。
+///
+/// # 引数
+/// - `ancestor_count`: チャンクを囲んだ祖先のタグの数(チャンク内での`document_order`はこの数から始まる)
+fn rebase_chunk(
+    mut out_put_values: Vec<Vec<OutPutTagValue>>,
+    tag_group_hierarchy: &[String],
+    chunk: &Chunk,
+    groups: &[GroupSpan],
+    ancestor_count: usize,
+) -> Vec<Vec<OutPutTagValue>> {
+    let first_group = &groups[chunk.first_group];
+    for (i, out_put_value) in out_put_values.iter_mut().enumerate() {
+        for out_put_tag_value in out_put_value.iter_mut() {
+            out_put_tag_value.set_group_index(chunk.first_group + i);
+            out_put_tag_value.set_document_order(
+                out_put_tag_value.get_document_order() - ancestor_count
+                    + first_group.document_order,
+            );
+            // グループのタグ自身は、チャンクの最初のグループからの順番になっている
+            if out_put_tag_value.get_tag_hierarchy() == tag_group_hierarchy {
+                out_put_tag_value.set_sibling_index(
+                    out_put_tag_value.get_sibling_index() + first_group.sibling_index,
+                );
+            }
+        }
+    }
+    out_put_values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::xml_read_config::TagValueName;
+
+    #[test_log::test]
+    fn test_read_xml_parallel_matches_read_xml() {
+        let text = TagValueName::new_tag_text;
+        let sample = std::fs::read_to_string("test_xml/sample1.xml").unwrap();
+        // `channel`と`programme`が交互に並ぶ、大きなXMLを作成する
+        let start = sample.find("  <channel ").unwrap();
+        let end = sample.rfind("</tv>").unwrap();
+        let mut xml = sample[..start].replace("<tv ", r#"<tv xml:lang="ja" "#);
+        for _ in 0..50 {
+            xml.push_str(&sample[start..end]);
+        }
+        xml.push_str("</tv>\n");
+
+        let config = build_read_config(
+            "tv/programme",
+            vec![
+                ("", TagValueName::new_attribute("start".to_string())),
+                ("title", text()),
+                ("category", text()),
+                ("extdesc/item", text()),
+                ("extdesc", TagValueName::new_outer_xml()),
+            ],
+        );
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        let expected = crate::reader::xml_reader::read_xml(&mut reader, &config).unwrap();
+        assert_eq!(expected.len(), 100);
+        for chunk_count in [1, 7, 1000] {
+            let actual =
+                read_xml_parallel_with_chunk_count(xml.as_bytes(), &config, chunk_count).unwrap();
+            assert_eq!(actual, expected, "chunk_count {}", chunk_count);
+        }
+        assert_eq!(expected[99].last().unwrap().get_lang(), Some("ja"));
+        assert_eq!(read_xml_parallel(&xml, &config).unwrap(), expected);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::str::FromStr;
+
+use super::group::{find_values, get_base_tag_hierarchy, resolve_tag_path};
+use super::output_tag_value::OutPutTagValue;
+use super::util::parse_tag_path;
+use super::xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig};
+
+#[cfg(feature = "derive")]
+pub use pick_up_xml_value_derive::PickUp;
+
+/// 読み取り設定と、グループから構造体への変換を持つ型を表します。
+///
+/// 通常は`#[derive(PickUp)]`で実装します。
+///
+/// ```ignore
+/// #[derive(PickUp)]
+/// #[pick(group = "tv/programme")]
+/// struct Programme {
+///     #[pick(attr = "start")]
+///     start: String,
+///     #[pick(path = "title", text)]
+///     title: Vec<String>,
+///     #[pick(path = "desc", text)]
+///     desc: Option<String>,
+/// }
+/// ```
+pub trait PickUp: Sized {
+    /// この型に必要な値を読み取る`XmlReadConfig`を作成します。
+    fn read_config() -> XmlReadConfig;
+
+    /// `read_xml`が返す1グループ分の`OutPutTagValue`から値を作成します。
+    fn from_group(group: &[OutPutTagValue]) -> Result<Self, Box<dyn Error>>;
+
+    /// `read_xml`の結果をすべて変換します。
+    fn from_groups(groups: &[Vec<OutPutTagValue>]) -> Result<Vec<Self>, Box<dyn Error>> {
+        groups.iter().map(|group| Self::from_group(group)).collect()
+    }
+}
+
+/// グループのタグ階層と、グループからの相対パスと取得する項目の組から`XmlReadConfig`を作成します。
+///
+/// 同じタグ階層の項目は1つの`XmlTagReadConfig`にまとめられます。
+/// グループのタグ階層の設定は、項目がない場合も必ず作成されます。
+///
+/// # 引数
+/// - `tag_group_hierarchy`: グループ化の単位となるタグのパス(例: `"tv/programme"`)
+/// - `targets`: グループからの相対パス(例: `"title"`、グループのタグ自身は`""`)と取得する項目の組
+pub fn build_read_config(
+    tag_group_hierarchy: &str,
+    targets: Vec<(&str, TagValueName)>,
+) -> XmlReadConfig {
+    let tag_group_hierarchy = parse_tag_path(tag_group_hierarchy);
+    let mut target_map: HashMap<Vec<String>, HashSet<TagValueName>> = HashMap::new();
+    target_map.insert(tag_group_hierarchy.clone(), HashSet::new());
+    for (path, tag_value_name) in targets {
+        let mut tag_hierarchy = tag_group_hierarchy.clone();
+        tag_hierarchy.extend(parse_tag_path(path));
+        target_map
+            .entry(tag_hierarchy)
+            .or_default()
+            .insert(tag_value_name);
+    }
+    let mut config = XmlReadConfig::new(tag_group_hierarchy);
+    for (tag_hierarchy, target_tag_value_names) in target_map {
+        // タグ階層は必ずグループのタグ階層から始まるためエラーにならない
+        let _ = config.insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+            tag_hierarchy,
+            target_tag_value_names,
+        ));
+    }
+    config
+}
+
+/// グループから、パスと項目に一致する値を出現順に取得します。
+///
+/// パスは`Group`と同じ規則(`Group::resolve_path`を参照)でタグ階層に変換します。
+/// グループのタグ階層は、グループ内で最も短いタグ階層とします。(`get_base_tag_hierarchy`を参照)
+pub fn pick_up_values(
+    group: &[OutPutTagValue],
+    path: &str,
+    tag_value_name: &TagValueName,
+) -> Vec<String> {
+    let tag_hierarchy = resolve_tag_path(&get_base_tag_hierarchy(group), path);
+    find_values(group, &tag_hierarchy, tag_value_name)
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_value<T>(field: &str, value: &str) -> Result<T, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.trim().parse::<T>().map_err(|e| {
+        let s_err = format!(
+            "FAILED TO PARSE VALUE. field {:?} , value {:?} : {}",
+            field, value, e
+        );
+        s_err.into()
+    })
+}
+
+/// 値がちょうど1つであることを確認して変換します。
+pub fn convert_single<T>(field: &str, values: Vec<String>) -> Result<T, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match values.as_slice() {
+        [value] => parse_value(field, value),
+        [] => Err(format!("VALUE NOT FOUND. field {:?}", field).into()),
+        _ => Err(format!(
+            "EXPECTED A SINGLE VALUE. field {:?} , values {:?}",
+            field, values
+        )
+        .into()),
+    }
+}
+
+/// 値が0個または1つであることを確認して変換します。
+pub fn convert_option<T>(field: &str, values: Vec<String>) -> Result<Option<T>, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if values.is_empty() {
+        Ok(None)
+    } else {
+        convert_single(field, values).map(Some)
+    }
+}
+
+/// すべての値を変換します。
+pub fn convert_vec<T>(field: &str, values: Vec<String>) -> Result<Vec<T>, Box<dyn Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    values
+        .iter()
+        .map(|value| parse_value(field, value))
+        .collect()
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use log::warn;
+
+use super::xml_read_config::XmlReadConfig;
+
+/// 取得中のタグのXMLです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawXml {
+    /// タグ階層
+    tag_hierarchy: Vec<String>,
+    /// 開始タグのバイト列
+    start_tag: Vec<u8>,
+    /// 開始タグと終了タグの間のバイト列
+    inner: Vec<u8>,
+}
+
+/// `inner_xml`・`outer_xml`を取得するために、イベントのバイト列を文書に書かれたとおりに保持します。
+///
+/// `quick_xml::Reader::read_event_into`に渡したバッファには、イベントのバイト列が
+/// 区切り文字(`<`・`>`)を除いてそのまま残るため、区切り文字を補うだけで元のマークアップになります。
+/// (再シリアライズは行わないので、属性の引用符や空白、実体参照は変わりません)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::reader) struct RawXmlCapture {
+    /// `inner_xml`・`outer_xml`を取得するタグ階層
+    target_tag_hierarchies: HashSet<Vec<String>>,
+    /// 取得中のタグ(外側のタグが先)
+    captures: Vec<RawXml>,
+}
+
+impl RawXmlCapture {
+    pub(in crate::reader) fn new(read_config: &XmlReadConfig) -> Self {
+        let target_tag_hierarchies = read_config
+            .get_tag_read_configs()
+            .filter(|config| config.has_raw_xml_target())
+            .map(|config| config.get_tag_hierarchy())
+            .collect();
+        RawXmlCapture {
+            target_tag_hierarchies,
+            captures: Vec::new(),
+        }
+    }
+
+    /// `inner_xml`・`outer_xml`を取得するタグがあるかどうかを確認します。
+    pub(in crate::reader) fn is_enabled(&self) -> bool {
+        !self.target_tag_hierarchies.is_empty()
+    }
+
+    /// 開始タグ(空要素タグを含む)のバイト列を追加し、取得対象のタグであれば取得を始めます。
+    pub(in crate::reader) fn start(&mut self, tag_hierarchy: &[String], raw: &[u8]) {
+        self.push(raw);
+        if self.target_tag_hierarchies.contains(tag_hierarchy) {
+            self.captures.push(RawXml {
+                tag_hierarchy: tag_hierarchy.to_vec(),
+                start_tag: raw.to_vec(),
+                inner: Vec::new(),
+            });
+        }
+    }
+
+    /// テキストやコメントなど、タグ以外のイベントのバイト列を追加します。
+    pub(in crate::reader) fn push(&mut self, raw: &[u8]) {
+        for capture in &mut self.captures {
+            capture.inner.extend_from_slice(raw);
+        }
+    }
+
+    /// 終了タグのバイト列を追加し、取得中のタグが終了した場合は取得を終えます。
+    /// (空要素タグの場合は`raw`を空にする)
+    ///
+    /// # 戻り値
+    /// - 取得したタグの内側のXMLとタグ全体のXML(取得中のタグではない場合は`None`)
+    pub(in crate::reader) fn end(
+        &mut self,
+        tag_hierarchy: &[String],
+        raw: &[u8],
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let finished = match self.captures.last() {
+            Some(capture) if capture.tag_hierarchy == tag_hierarchy => self.captures.pop(),
+            _ => None,
+        };
+        self.push(raw);
+        finished.map(|capture| {
+            let mut outer = capture.start_tag;
+            outer.extend_from_slice(&capture.inner);
+            outer.extend_from_slice(raw);
+            (capture.inner, outer)
+        })
+    }
+}
+
+/// `read_event_into`のバッファから、イベントのバイト列を文書に書かれたとおりに復元します。
+///
+/// # 引数
+/// - `buf`: イベントを読み込んだ直後のバッファ
+/// - `is_markup`: タグ・コメントなど`<`と`>`で囲まれたイベントであれば`true`
+/// - `consumed`: イベントの読み込みで進んだバイト数(`buffer_position`の差)
+pub(in crate::reader) fn raw_event_bytes(buf: &[u8], is_markup: bool, consumed: u64) -> Vec<u8> {
+    // 空要素タグを展開した終了タグなど、文書上に存在しないイベント
+    if consumed == 0 {
+        return Vec::new();
+    }
+    let raw = if is_markup {
+        [b"<", buf, b">"].concat()
+    } else {
+        buf.to_vec()
+    };
+    if raw.len() as u64 != consumed {
+        warn!(
+            "RAW XML MAY DIFFER FROM THE DOCUMENT (TEXT TRIMMING IS ENABLED?). consumed {} bytes, restored {} bytes",
+            consumed,
+            raw.len()
+        );
+    }
+    raw
+}
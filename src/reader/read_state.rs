@@ -0,0 +1,229 @@
+use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use quick_xml::events::attributes::Attributes;
+
+use super::output_tag_value::OutPutTagValue;
+use super::output_tag_value_ref::OutPutTagValueRef;
+use super::tag_matcher::{NodeId, TagHierarchyMatcher};
+use super::xml_read_config::{XmlReadConfig, XmlTagReadConfig};
+
+/// グループにまとめる値です。(`OutPutTagValue`・`OutPutTagValueRef`)
+pub(in crate::reader) trait GroupValue {
+    /// 何番目のグループに含まれるかを設定します。
+    fn set_group_index(&mut self, group_index: usize);
+}
+
+impl GroupValue for OutPutTagValue {
+    fn set_group_index(&mut self, group_index: usize) {
+        OutPutTagValue::set_group_index(self, group_index);
+    }
+}
+
+impl GroupValue for OutPutTagValueRef<'_> {
+    fn set_group_index(&mut self, group_index: usize) {
+        OutPutTagValueRef::set_group_index(self, group_index);
+    }
+}
+
+/// 開始タグ(空要素タグを含む)の言語と順番です。
+pub(in crate::reader) struct StartedTag<L> {
+    /// タグの言語(`xml:lang`属性、`lang`属性、祖先のタグの`xml:lang`属性の順)
+    pub(in crate::reader) lang: Option<L>,
+    /// 文書内での開始タグの順番
+    pub(in crate::reader) document_order: usize,
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目か
+    pub(in crate::reader) sibling_index: usize,
+}
+
+/// 読み込み中のタグの状態です。(`read_xml`・`read_xml_ref`で共通)
+///
+/// 開いているタグのタグ階層・トライ木のノード・引き継がれる`xml:lang`・兄弟タグの数と、
+/// 文書内での開始タグの順番・完成したグループの数を保持します。
+///
+/// # 型引数
+/// - `N`: タグ名(`String`、または読み込んだXMLから借用した`&str`)
+/// - `L`: 言語(`String`、または`Cow<str>`)
+pub(in crate::reader) struct ReadState<'c, N, L> {
+    /// 読み取り設定のタグ階層をまとめたトライ木
+    matcher: TagHierarchyMatcher<'c>,
+    /// 開いているタグのタグ階層
+    tag_hierarchy: Vec<N>,
+    /// `tag_hierarchy`のタグごとのノード
+    matched_nodes: Vec<Option<NodeId>>,
+    /// 開いているタグごとの、子孫に引き継がれる`xml:lang`
+    xml_lang_stack: Vec<Option<L>>,
+    /// 開いているタグごとの、子タグのタグ名ごとの数(先頭は文書レベル)
+    sibling_counts_stack: Vec<HashMap<N, usize>>,
+    /// 文書内での開始タグの順番
+    document_order: usize,
+    /// 完成したグループの数
+    group_index: usize,
+}
+
+impl<'c, N, L> ReadState<'c, N, L>
+where
+    N: Borrow<str> + Clone + Eq + Hash + Debug,
+    L: Clone,
+{
+    pub(in crate::reader) fn new(read_config: &'c XmlReadConfig) -> Self {
+        ReadState {
+            matcher: TagHierarchyMatcher::new(read_config),
+            tag_hierarchy: Vec::new(),
+            matched_nodes: Vec::new(),
+            xml_lang_stack: Vec::new(),
+            sibling_counts_stack: vec![HashMap::new()],
+            document_order: 0,
+            group_index: 0,
+        }
+    }
+
+    /// 開いているタグのタグ階層を取得します。
+    pub(in crate::reader) fn get_tag_hierarchy(&self) -> &Vec<N> {
+        &self.tag_hierarchy
+    }
+
+    /// 最後に開いたタグのノードを取得します。(タグの外では文書レベルのノード)
+    pub(in crate::reader) fn current_node(&self) -> Option<NodeId> {
+        self.matched_nodes
+            .last()
+            .copied()
+            .unwrap_or(Some(TagHierarchyMatcher::ROOT))
+    }
+
+    /// 最後に開いたタグの読み取り設定を取得します。
+    pub(in crate::reader) fn get_config(&self) -> Option<&'c XmlTagReadConfig> {
+        self.matcher.get_config(self.current_node())
+    }
+
+    /// 最後に開いたタグのテキストを取得するかどうかを確認します。
+    pub(in crate::reader) fn is_text_target(&self) -> bool {
+        self.matcher.is_text_target(self.current_node())
+    }
+
+    /// 開始タグ・空要素タグを開きます。
+    ///
+    /// # 引数
+    /// - `elm_name`: タグ名
+    /// - `is_empty`: 空要素タグかどうか(空要素タグは、`end_tag`で`xml:lang`・兄弟タグの数を戻さない)
+    /// - `xml_lang`・`lang`: タグの`xml:lang`属性・`lang`属性(`get_lang_attributes`で取得)
+    pub(in crate::reader) fn start_tag(
+        &mut self,
+        elm_name: N,
+        is_empty: bool,
+        xml_lang: Option<L>,
+        lang: Option<L>,
+    ) -> StartedTag<L> {
+        let sibling_index = self.next_sibling_index(&elm_name);
+        if is_empty || self.tag_hierarchy.last() != Some(&elm_name) {
+            let node = self.matcher.child(self.current_node(), elm_name.borrow());
+            self.matched_nodes.push(node);
+            self.tag_hierarchy.push(elm_name);
+        }
+        let inherited = self.xml_lang_stack.last().cloned().flatten();
+        let lang = xml_lang.clone().or(lang).or_else(|| inherited.clone());
+        if !is_empty {
+            self.xml_lang_stack.push(xml_lang.or(inherited));
+            self.sibling_counts_stack.push(HashMap::new());
+        }
+        let document_order = self.document_order;
+        self.document_order += 1;
+        StartedTag {
+            lang,
+            document_order,
+            sibling_index,
+        }
+    }
+
+    /// 終了タグが、最後に開いたタグと一致するかどうかを確認します。
+    pub(in crate::reader) fn check_end_tag(&self, elm_name: &[u8]) -> Result<(), Box<dyn Error>> {
+        let last = self.tag_hierarchy.last().map(|x| x.borrow().as_bytes());
+        if last != Some(elm_name) {
+            let s = format!(
+                "tags are mismatched! current_tag_hierarchy : {:?} , elm_name : {:?} !!",
+                self.tag_hierarchy,
+                String::from_utf8_lossy(elm_name)
+            );
+            return Err(s.into());
+        }
+        Ok(())
+    }
+
+    /// 最後に開いたタグを閉じます。
+    ///
+    /// 読み取り設定のあるタグであれば、タグの値(`value`)をグループに追加し、
+    /// グループの終了であれば`on_group`を呼び出します。
+    ///
+    /// # 引数
+    /// - `is_empty`: 空要素タグかどうか(`start_tag`と同じ)
+    /// - `value`: 閉じるタグの値(値を保持していない場合は`None`)
+    pub(in crate::reader) fn end_tag<T, F>(
+        &mut self,
+        is_empty: bool,
+        value: Option<T>,
+        out_put_value: &mut Vec<T>,
+        on_group: &mut F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        T: GroupValue,
+        F: FnMut(Vec<T>) -> Result<(), Box<dyn Error>>,
+    {
+        let node = self.current_node();
+        if self.matcher.get_config(node).is_some() {
+            if let Some(mut value) = value {
+                value.set_group_index(self.group_index);
+                out_put_value.push(value);
+            }
+            if self.matcher.is_group(node) {
+                on_group(std::mem::take(out_put_value))?;
+                self.group_index += 1;
+            }
+        }
+        self.matched_nodes.pop();
+        self.tag_hierarchy.pop();
+        if !is_empty {
+            self.xml_lang_stack.pop();
+            self.sibling_counts_stack.pop();
+        }
+        Ok(())
+    }
+
+    /// 親タグの中で、同じタグ名の兄弟タグのうち何番目(0から始まる)かを数えます。
+    fn next_sibling_index(&mut self, elm_name: &N) -> usize {
+        let Some(sibling_counts) = self.sibling_counts_stack.last_mut() else {
+            return 0;
+        };
+        match sibling_counts.get_mut(elm_name.borrow()) {
+            Some(count) => {
+                *count += 1;
+                *count - 1
+            }
+            None => {
+                sibling_counts.insert(elm_name.clone(), 1);
+                0
+            }
+        }
+    }
+}
+
+/// 開始タグの`xml:lang`属性と`lang`属性を取得します。(言語の決定は`ReadState::start_tag`で行う)
+pub(in crate::reader) fn get_lang_attributes(
+    attributes: Attributes<'_>,
+) -> (Option<Cow<'_, str>>, Option<Cow<'_, str>>) {
+    let mut xml_lang = None;
+    let mut lang = None;
+    for attr in attributes.flatten() {
+        let slot = match attr.key.as_ref() {
+            b"xml:lang" => &mut xml_lang,
+            b"lang" => &mut lang,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = attr.unescape_value().ok();
+        }
+    }
+    (xml_lang, lang)
+}
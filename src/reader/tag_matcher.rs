@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use super::xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig};
+
+/// トライ木のノードの番号です。
+pub(in crate::reader) type NodeId = usize;
+
+/// トライ木のノード(1つのタグ階層)です。
+#[derive(Debug, Clone, Default)]
+struct Node<'a> {
+    /// 子タグのタグ名と、そのノード(タグ名は設定から借用する)
+    children: HashMap<&'a str, NodeId>,
+    /// このタグ階層の読み取り設定(設定がない途中のタグ階層は`None`)
+    config: Option<&'a XmlTagReadConfig>,
+    /// テキストを取得するかどうか
+    is_text_target: bool,
+    /// グループ化の単位となるタグ階層かどうか
+    is_group: bool,
+}
+
+/// `XmlReadConfig`のタグ階層を、タグ名のトライ木にまとめたものです。
+///
+/// `read_xml`では、開いているタグごとにノードを保持し、子タグのノードを1回の検索で求めます。
+/// 設定を複製せずに借用するため、イベントごとに`HashMap`や`HashSet`を作成しません。
+#[derive(Debug, Clone)]
+pub(in crate::reader) struct TagHierarchyMatcher<'a> {
+    nodes: Vec<Node<'a>>,
+}
+
+impl<'a> TagHierarchyMatcher<'a> {
+    /// 文書レベル(タグの外)のノードです。
+    pub(in crate::reader) const ROOT: NodeId = 0;
+
+    pub(in crate::reader) fn new(read_config: &'a XmlReadConfig) -> Self {
+        let mut matcher = TagHierarchyMatcher {
+            nodes: vec![Node::default()],
+        };
+        for config in read_config.get_tag_read_configs() {
+            let node = matcher.insert(config.get_tag_hierarchy_ref());
+            matcher.nodes[node].config = Some(config);
+            matcher.nodes[node].is_text_target =
+                config.contains_target_tag_value_name(&TagValueName::new_tag_text());
+        }
+        // グループ化の単位となるタグ階層は、読み取り設定がなくてもノードを作成する
+        let tag_group_hierarchy = read_config.get_tag_group_hierarchy();
+        if let Some(node) = matcher.find(&tag_group_hierarchy) {
+            matcher.nodes[node].is_group = true;
+        }
+        matcher
+    }
+
+    /// タグ階層のノードを作成します。(途中のタグ階層のノードも作成する)
+    fn insert(&mut self, tag_hierarchy: &'a [String]) -> NodeId {
+        let mut node = Self::ROOT;
+        for tag_name in tag_hierarchy {
+            node = match self.nodes[node].children.get(tag_name.as_str()) {
+                Some(child) => *child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(tag_name.as_str(), child);
+                    child
+                }
+            };
+        }
+        node
+    }
+
+    /// タグ階層のノードを検索します。
+    fn find(&self, tag_hierarchy: &[String]) -> Option<NodeId> {
+        tag_hierarchy.iter().try_fold(Self::ROOT, |node, tag_name| {
+            self.child(Some(node), tag_name)
+        })
+    }
+
+    /// 子タグのノードを取得します。
+    ///
+    /// # 戻り値
+    /// - 子タグ以下に読み取り設定がない場合は`None`(`parent`が`None`の場合も`None`)
+    pub(in crate::reader) fn child(
+        &self,
+        parent: Option<NodeId>,
+        tag_name: &str,
+    ) -> Option<NodeId> {
+        self.nodes[parent?].children.get(tag_name).copied()
+    }
+
+    /// ノードの読み取り設定を取得します。
+    pub(in crate::reader) fn get_config(
+        &self,
+        node: Option<NodeId>,
+    ) -> Option<&'a XmlTagReadConfig> {
+        self.nodes[node?].config
+    }
+
+    /// ノードのテキストを取得するかどうかを確認します。
+    pub(in crate::reader) fn is_text_target(&self, node: Option<NodeId>) -> bool {
+        node.is_some_and(|node| self.nodes[node].is_text_target)
+    }
+
+    /// ノードがグループ化の単位となるタグ階層かどうかを確認します。
+    pub(in crate::reader) fn is_group(&self, node: Option<NodeId>) -> bool {
+        node.is_some_and(|node| self.nodes[node].is_group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+
+    #[test_log::test]
+    fn test_tag_hierarchy_matcher() {
+        let read_config = build_read_config(
+            "tv/programme",
+            vec![
+                ("title", TagValueName::new_tag_text()),
+                (
+                    "video/aspect",
+                    TagValueName::new_attribute("lang".to_string()),
+                ),
+            ],
+        );
+        let matcher = TagHierarchyMatcher::new(&read_config);
+        let root = Some(TagHierarchyMatcher::ROOT);
+        let tv = matcher.child(root, "tv");
+        assert!(tv.is_some());
+        assert!(matcher.get_config(tv).is_none());
+
+        let programme = matcher.child(tv, "programme");
+        assert!(matcher.is_group(programme));
+        assert!(matcher.get_config(programme).is_some());
+        assert!(!matcher.is_text_target(programme));
+
+        let title = matcher.child(programme, "title");
+        assert!(matcher.is_text_target(title));
+        assert!(!matcher.is_group(title));
+
+        let video = matcher.child(programme, "video");
+        assert!(matcher.get_config(video).is_none());
+        let aspect = matcher.child(video, "aspect");
+        assert!(!matcher.is_text_target(aspect));
+        assert_eq!(
+            matcher.get_config(aspect).unwrap().get_tag_hierarchy_ref(),
+            ["tv", "programme", "video", "aspect"]
+        );
+
+        // 設定のないタグの子孫は、すべて`None`になる
+        let channel = matcher.child(tv, "channel");
+        assert!(channel.is_none());
+        assert!(matcher.child(channel, "display-name").is_none());
+        assert!(matcher.child(root, "programme").is_none());
+    }
+}
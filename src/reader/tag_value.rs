@@ -1,10 +1,19 @@
+use chrono::{DateTime, FixedOffset};
+
 use super::tag_value_type::TagValueType;
+use super::typed_value::{
+    TypedValue, ValueConversionError, ValueKind, parse_bool, parse_date_time, parse_float,
+    parse_integer,
+};
+use super::xml_read_config::TagValueName;
+use super::xmltv::time::parse_xmltv_time;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TagValue {
     /// 種類
     value_type: TagValueType,
-    /// 名前(タグのテキストであればタグ名が入る。属性の場合は属性名が入る。)
+    /// 名前(タグのテキスト・XML・コメントであればタグ名が入る。属性の場合は属性名、処理命令の場合はターゲット名が入る。)
     name: String,
     /// 値
     value: String,
@@ -32,4 +41,94 @@ impl TagValue {
     pub fn get_value_type(&self) -> &TagValueType {
         &self.value_type
     }
+
+    /// この値に該当する`TagValueName`を取得します。
+    pub fn get_tag_value_name(&self) -> TagValueName {
+        match self.value_type {
+            TagValueType::TagText => TagValueName::new_tag_text(),
+            TagValueType::AttributeValue => TagValueName::new_attribute(self.name.clone()),
+            TagValueType::InnerXml => TagValueName::new_inner_xml(),
+            TagValueType::OuterXml => TagValueName::new_outer_xml(),
+            TagValueType::Comment => TagValueName::new_comment(),
+            TagValueType::ProcessingInstruction => {
+                TagValueName::new_processing_instruction(self.name.clone())
+            }
+        }
+    }
+
+    /// 値を指定した種類に変換します。
+    ///
+    /// # 戻り値
+    /// - `Ok(TypedValue)`: 変換できた場合
+    /// - `Err(ValueConversionError)`: 変換できなかった場合(タグ階層は空)
+    pub fn get_typed_value(
+        &self,
+        value_kind: &ValueKind,
+    ) -> Result<TypedValue, ValueConversionError> {
+        self.convert_value(value_kind, |value| value_kind.convert(value))
+    }
+
+    /// 値を整数として取得します。
+    pub fn get_i64(&self) -> Result<i64, ValueConversionError> {
+        self.convert_value(&ValueKind::Integer, parse_integer)
+    }
+
+    /// 値を浮動小数点数として取得します。
+    pub fn get_f64(&self) -> Result<f64, ValueConversionError> {
+        self.convert_value(&ValueKind::Float, parse_float)
+    }
+
+    /// 値を真偽値(`true`、`false`、`1`、`0`)として取得します。
+    pub fn get_bool(&self) -> Result<bool, ValueConversionError> {
+        self.convert_value(&ValueKind::Bool, parse_bool)
+    }
+
+    /// 値を書式(`chrono`の書式。タイムゾーンを含むこと)に従って日時として取得します。
+    pub fn get_date_time(
+        &self,
+        format: &str,
+    ) -> Result<DateTime<FixedOffset>, ValueConversionError> {
+        self.convert_value(&ValueKind::DateTime(format.to_string()), |value| {
+            parse_date_time(value, format)
+        })
+    }
+
+    /// 値をXMLTVの日時(`YYYYMMDDhhmmss +zzzz`)として取得します。
+    pub fn get_xmltv_time(&self) -> Result<DateTime<FixedOffset>, ValueConversionError> {
+        self.convert_value(&ValueKind::XmltvTime, parse_xmltv_time)
+    }
+
+    /// 値を`parse`で変換します。(変換できなかった場合は、`value_kind`を含むエラーを返す)
+    fn convert_value<T>(
+        &self,
+        value_kind: &ValueKind,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Result<T, ValueConversionError> {
+        parse(&self.value).map_err(|reason| {
+            ValueConversionError::new(self.get_tag_value_name(), &self.value, value_kind, reason)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_typed_accessors() {
+        let event_id = TagValue::new(
+            TagValueType::AttributeValue,
+            "event_id".to_string(),
+            "32665".to_string(),
+        );
+        assert_eq!(event_id.get_i64().unwrap(), 32665);
+        assert_eq!(event_id.get_f64().unwrap(), 32665.0);
+        let err = event_id.get_bool().unwrap_err();
+        assert_eq!(err.get_tag_value_name().to_string(), "@event_id");
+        assert_eq!(err.get_value(), "32665");
+
+        let free_ca = TagValue::new(TagValueType::TagText, "freeCA".to_string(), "0".to_string());
+        assert!(!free_ca.get_bool().unwrap());
+        assert!(free_ca.get_date_time("%Y%m%d%H%M%S %z").is_err());
+    }
 }
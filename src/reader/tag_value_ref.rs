@@ -0,0 +1,97 @@
+use std::borrow::Cow;
+
+use super::tag_value::TagValue;
+use super::tag_value_type::TagValueType;
+use super::typed_value::{TypedValue, ValueConversionError, ValueKind};
+use super::xml_read_config::TagValueName;
+
+/// `TagValue`の借用版です。(`read_xml_ref`で取得)
+///
+/// 名前と値は、読み込んだXMLや読み取り設定から借用します。
+/// 実体参照を含む値など、文書に書かれたとおりではない値のみ`Cow::Owned`になります。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TagValueRef<'a> {
+    /// 種類
+    value_type: TagValueType,
+    /// 名前(`TagValue`と同じ)
+    name: Cow<'a, str>,
+    /// 値
+    value: Cow<'a, str>,
+}
+
+impl<'a> TagValueRef<'a> {
+    pub fn new(value_type: TagValueType, name: Cow<'a, str>, value: Cow<'a, str>) -> Self {
+        TagValueRef {
+            value_type,
+            name,
+            value,
+        }
+    }
+
+    /// タグ名を取得します。
+    pub fn get_tag_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 値を取得します。
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// タグの種類を取得します。
+    pub fn get_value_type(&self) -> &TagValueType {
+        &self.value_type
+    }
+
+    /// 名前と値のどちらも借用しているかどうかを確認します。
+    pub fn is_borrowed(&self) -> bool {
+        matches!(
+            (&self.name, &self.value),
+            (Cow::Borrowed(_), Cow::Borrowed(_))
+        )
+    }
+
+    /// この値に該当する`TagValueName`を取得します。
+    pub fn get_tag_value_name(&self) -> TagValueName {
+        match self.value_type {
+            TagValueType::TagText => TagValueName::new_tag_text(),
+            TagValueType::AttributeValue => TagValueName::new_attribute(self.name.to_string()),
+            TagValueType::InnerXml => TagValueName::new_inner_xml(),
+            TagValueType::OuterXml => TagValueName::new_outer_xml(),
+            TagValueType::Comment => TagValueName::new_comment(),
+            TagValueType::ProcessingInstruction => {
+                TagValueName::new_processing_instruction(self.name.to_string())
+            }
+        }
+    }
+
+    /// 値を指定した種類に変換します。(`TagValue::get_typed_value`を参照)
+    pub fn get_typed_value(
+        &self,
+        value_kind: &ValueKind,
+    ) -> Result<TypedValue, ValueConversionError> {
+        value_kind.convert(&self.value).map_err(|reason| {
+            ValueConversionError::new(self.get_tag_value_name(), &self.value, value_kind, reason)
+        })
+    }
+
+    /// 所有する`TagValue`に変換します。
+    pub fn into_owned(self) -> TagValue {
+        TagValue::new(
+            self.value_type,
+            self.name.into_owned(),
+            self.value.into_owned(),
+        )
+    }
+}
+
+impl<'a> From<&'a TagValue> for TagValueRef<'a> {
+    fn from(tag_value: &'a TagValue) -> Self {
+        TagValueRef::new(
+            tag_value.get_value_type().clone(),
+            Cow::Borrowed(tag_value.get_tag_name()),
+            Cow::Borrowed(tag_value.get_value()),
+        )
+    }
+}
@@ -1,7 +1,21 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+#[non_exhaustive]
 pub enum TagValueType {
     /// タグのテキスト
     TagText,
     /// タグの属性
     AttributeValue,
+    /// タグの内側のXML(開始タグと終了タグの間のマークアップをそのまま)
+    InnerXml,
+    /// タグ全体のXML(開始タグから終了タグまでのマークアップをそのまま)
+    OuterXml,
+    /// コメント(`<!-- ... -->`)
+    Comment,
+    /// 処理命令(`<?target ...?>`)
+    ProcessingInstruction,
 }
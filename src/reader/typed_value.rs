@@ -0,0 +1,389 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset};
+
+use super::xml_read_config::TagValueName;
+use super::xmltv::time::parse_xmltv_time;
+
+/// 値の種類を表します。
+///
+/// `XmlTagReadConfig::set_value_kind`で取得する項目ごとに宣言し、
+/// `XmlReadConfig::convert_tag_value`などで`TypedValue`に変換します。
+///
+/// 文字列では以下のように表します。(設定ファイルの`types`でも使用します)
+///
+/// | 種類                     | 文字列                       |
+/// |--------------------------|------------------------------|
+/// | 文字列(変換しない)       | `string`                     |
+/// | 整数                     | `integer`                    |
+/// | 浮動小数点数             | `float`                      |
+/// | 真偽値                   | `bool`                       |
+/// | 書式を指定した日時       | `datetime:%Y%m%d%H%M%S %z`   |
+/// | XMLTVの日時              | `xmltv-time`                 |
+/// | 列挙値                   | `enum:4:3\|16:9`             |
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    /// 文字列(変換しない)
+    #[default]
+    String,
+    /// 整数(`i64`)
+    Integer,
+    /// 浮動小数点数(`f64`)
+    Float,
+    /// 真偽値(`true`、`false`、`1`、`0`)
+    Bool,
+    /// 日時(`chrono`の書式。タイムゾーン(`%z`など)を含むこと)
+    DateTime(String),
+    /// XMLTVの日時(`YYYYMMDDhhmmss +zzzz`。`parse_xmltv_time`を参照)
+    XmltvTime,
+    /// 列挙値(いずれかの値と一致すること)
+    Enum(Vec<String>),
+}
+
+impl ValueKind {
+    /// 文字列を、この種類の値に変換します。
+    ///
+    /// # 戻り値
+    /// - `Ok(TypedValue)`: 変換できた場合
+    /// - `Err(String)`: 変換できなかった場合、理由を含む
+    pub fn convert(&self, value: &str) -> Result<TypedValue, String> {
+        let trimmed = value.trim();
+        match self {
+            ValueKind::String => Ok(TypedValue::String(value.to_string())),
+            ValueKind::Integer => parse_integer(value).map(TypedValue::Integer),
+            ValueKind::Float => parse_float(value).map(TypedValue::Float),
+            ValueKind::Bool => parse_bool(value).map(TypedValue::Bool),
+            ValueKind::DateTime(format) => parse_date_time(value, format).map(TypedValue::DateTime),
+            ValueKind::XmltvTime => parse_xmltv_time(trimmed).map(TypedValue::DateTime),
+            ValueKind::Enum(variants) => {
+                if variants.iter().any(|x| x == trimmed) {
+                    Ok(TypedValue::Enum(trimmed.to_string()))
+                } else {
+                    Err(format!("expected one of {:?}", variants))
+                }
+            }
+        }
+    }
+}
+
+/// 文字列を整数(`ValueKind::Integer`)に変換します。(前後の空白は除く)
+pub(crate) fn parse_integer(value: &str) -> Result<i64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|e: std::num::ParseIntError| e.to_string())
+}
+
+/// 文字列を浮動小数点数(`ValueKind::Float`)に変換します。(前後の空白は除く)
+pub(crate) fn parse_float(value: &str) -> Result<f64, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|e: std::num::ParseFloatError| e.to_string())
+}
+
+/// 文字列を真偽値(`ValueKind::Bool`)に変換します。(前後の空白は除く)
+pub(crate) fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err("expected true, false, 1 or 0".to_string()),
+    }
+}
+
+/// 文字列を書式に従って日時(`ValueKind::DateTime`)に変換します。(前後の空白は除く)
+pub(crate) fn parse_date_time(value: &str, format: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_str(value.trim(), format)
+        .map_err(|e| format!("{} (format {:?})", e, format))
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueKind::String => f.write_str("string"),
+            ValueKind::Integer => f.write_str("integer"),
+            ValueKind::Float => f.write_str("float"),
+            ValueKind::Bool => f.write_str("bool"),
+            ValueKind::DateTime(format) => write!(f, "datetime:{}", format),
+            ValueKind::XmltvTime => f.write_str("xmltv-time"),
+            ValueKind::Enum(variants) => write!(f, "enum:{}", variants.join("|")),
+        }
+    }
+}
+
+/// 文字列から`ValueKind`を作成します。(書式は`Display`と同じ)
+impl FromStr for ValueKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (kind, argument) = match s.split_once(':') {
+            Some((kind, argument)) => (kind.trim(), Some(argument)),
+            None => (s, None),
+        };
+        match (kind, argument) {
+            ("string", None) => Ok(ValueKind::String),
+            ("integer", None) => Ok(ValueKind::Integer),
+            ("float", None) => Ok(ValueKind::Float),
+            ("bool", None) => Ok(ValueKind::Bool),
+            ("xmltv-time", None) => Ok(ValueKind::XmltvTime),
+            ("datetime", Some(format)) if !format.trim().is_empty() => {
+                Ok(ValueKind::DateTime(format.trim().to_string()))
+            }
+            ("enum", Some(variants)) if !variants.trim().is_empty() => Ok(ValueKind::Enum(
+                variants.split('|').map(|x| x.trim().to_string()).collect(),
+            )),
+            ("datetime", _) => Err(format!("format is empty in {:?}", s)),
+            ("enum", _) => Err(format!("variants are empty in {:?}", s)),
+            _ => Err(format!(
+                "unknown value kind {:?} (expected string, integer, float, bool, datetime:FORMAT, xmltv-time or enum:A|B)",
+                s
+            )),
+        }
+    }
+}
+
+/// 種類に応じて変換した値です。
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    /// 文字列
+    String(String),
+    /// 整数
+    Integer(i64),
+    /// 浮動小数点数
+    Float(f64),
+    /// 真偽値
+    Bool(bool),
+    /// 日時
+    DateTime(DateTime<FixedOffset>),
+    /// 列挙値
+    Enum(String),
+}
+
+impl TypedValue {
+    /// 文字列または列挙値であれば、その値を取得します。
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TypedValue::String(value) | TypedValue::Enum(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// 整数であれば、その値を取得します。
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            TypedValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// 浮動小数点数(または整数)であれば、その値を取得します。
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TypedValue::Float(value) => Some(*value),
+            TypedValue::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// 真偽値であれば、その値を取得します。
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TypedValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// 日時であれば、その値を取得します。
+    pub fn as_date_time(&self) -> Option<&DateTime<FixedOffset>> {
+        match self {
+            TypedValue::DateTime(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// 値を変換できなかったことを表すエラーです。
+///
+/// タグ階層・項目・値・種類を含みます。
+/// (`TagValue`から直接変換した場合、タグ階層は空になります)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueConversionError {
+    // `Result`が大きくならないように`Box`に入れる
+    detail: Box<ValueConversionErrorDetail>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ValueConversionErrorDetail {
+    tag_hierarchy: Vec<String>,
+    tag_value_name: TagValueName,
+    value: String,
+    value_kind: ValueKind,
+    reason: String,
+}
+
+impl ValueConversionError {
+    pub(in crate::reader) fn new(
+        tag_value_name: TagValueName,
+        value: &str,
+        value_kind: &ValueKind,
+        reason: String,
+    ) -> Self {
+        ValueConversionError {
+            detail: Box::new(ValueConversionErrorDetail {
+                tag_hierarchy: Vec::new(),
+                tag_value_name,
+                value: value.to_string(),
+                value_kind: value_kind.clone(),
+                reason,
+            }),
+        }
+    }
+
+    pub(in crate::reader) fn with_tag_hierarchy(mut self, tag_hierarchy: &[String]) -> Self {
+        self.detail.tag_hierarchy = tag_hierarchy.to_vec();
+        self
+    }
+
+    /// タグ階層を取得します。
+    pub fn get_tag_hierarchy(&self) -> &[String] {
+        &self.detail.tag_hierarchy
+    }
+
+    /// 項目を取得します。
+    pub fn get_tag_value_name(&self) -> &TagValueName {
+        &self.detail.tag_value_name
+    }
+
+    /// 変換できなかった値を取得します。
+    pub fn get_value(&self) -> &str {
+        &self.detail.value
+    }
+
+    /// 変換しようとした種類を取得します。
+    pub fn get_value_kind(&self) -> &ValueKind {
+        &self.detail.value_kind
+    }
+}
+
+impl Display for ValueConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CANNOT CONVERT VALUE. ")?;
+        let detail = &self.detail;
+        if !detail.tag_hierarchy.is_empty() {
+            write!(f, "{}/", detail.tag_hierarchy.join("/"))?;
+        }
+        write!(
+            f,
+            "{} {:?} to {}: {}",
+            detail.tag_value_name, detail.value, detail.value_kind, detail.reason
+        )
+    }
+}
+
+impl std::error::Error for ValueConversionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_value_kind_convert() {
+        assert_eq!(
+            ValueKind::Integer.convert(" 21 ").unwrap(),
+            TypedValue::Integer(21)
+        );
+        assert_eq!(ValueKind::Float.convert("2.5").unwrap().as_f64(), Some(2.5));
+        assert_eq!(
+            ValueKind::Bool.convert("0").unwrap(),
+            TypedValue::Bool(false)
+        );
+        assert!(ValueKind::Bool.convert("yes").is_err());
+        let start = ValueKind::DateTime("%Y%m%d%H%M%S %z".to_string())
+            .convert("20250620050000 +0900")
+            .unwrap();
+        assert_eq!(
+            start.as_date_time().unwrap().to_rfc3339(),
+            "2025-06-20T05:00:00+09:00"
+        );
+        let aspect = ValueKind::Enum(vec!["4:3".to_string(), "16:9".to_string()]);
+        assert_eq!(aspect.convert("16:9").unwrap().as_str(), Some("16:9"));
+        assert!(aspect.convert("21:9").is_err());
+    }
+
+    #[test_log::test]
+    fn test_value_kind_from_str() {
+        for kind in [
+            ValueKind::String,
+            ValueKind::Integer,
+            ValueKind::Float,
+            ValueKind::Bool,
+            ValueKind::DateTime("%Y%m%d%H%M%S %z".to_string()),
+            ValueKind::XmltvTime,
+            ValueKind::Enum(vec!["4:3".to_string(), "16:9".to_string()]),
+        ] {
+            assert_eq!(kind.to_string().parse::<ValueKind>().unwrap(), kind);
+        }
+        assert!("datetime".parse::<ValueKind>().is_err());
+        assert!("number".parse::<ValueKind>().is_err());
+    }
+
+    #[test_log::test]
+    fn test_get_typed_values() {
+        use std::collections::HashSet;
+
+        use crate::reader::util::string_vec;
+        use crate::reader::xml_read_config::{XmlReadConfig, XmlTagReadConfig};
+        use crate::reader::xml_reader::read_xml;
+
+        let programme = string_vec(vec!["tv", "programme"]);
+        let mut tag_read_config = XmlTagReadConfig::new(
+            programme.clone(),
+            HashSet::from_iter(vec![
+                TagValueName::new_attribute("event_id".to_string()),
+                TagValueName::new_attribute("channel".to_string()),
+            ]),
+        );
+        tag_read_config
+            .set_value_kind(
+                TagValueName::new_attribute("event_id".to_string()),
+                ValueKind::Integer,
+            )
+            .unwrap();
+        assert!(
+            tag_read_config
+                .set_value_kind(TagValueName::new_tag_text(), ValueKind::Integer)
+                .is_err()
+        );
+        let mut config = XmlReadConfig::new(programme.clone());
+        config
+            .insert_xml_tag_read_config_to_hash_map(&tag_read_config)
+            .unwrap();
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let groups = read_xml(&mut reader, &config).unwrap();
+        let typed_values = groups[0][0].get_typed_values(&config).unwrap();
+        assert!(typed_values.contains(&TypedValue::Integer(32665)));
+        assert!(typed_values.contains(&TypedValue::String("GR2_1032".to_string())));
+
+        // 宣言した種類に変換できない場合は、タグ階層と値がエラーに含まれる
+        tag_read_config
+            .set_value_kind(
+                TagValueName::new_attribute("channel".to_string()),
+                ValueKind::Integer,
+            )
+            .unwrap();
+        config
+            .insert_xml_tag_read_config_to_hash_map(&tag_read_config)
+            .unwrap();
+        let err = groups[0][0].get_typed_values(&config).unwrap_err();
+        assert_eq!(err.get_tag_hierarchy(), programme.as_slice());
+        assert_eq!(err.get_value(), "GR2_1032");
+        assert!(
+            err.to_string()
+                .contains("tv/programme/@channel \"GR2_1032\" to integer"),
+            "{}",
+            err
+        );
+    }
+}
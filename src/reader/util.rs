@@ -1,16 +1,14 @@
 use std::error::Error;
 
-use quick_xml::events::{BytesEnd, BytesStart};
+use quick_xml::events::BytesStart;
 
 pub(in crate::reader) enum BytesTag<'a> {
     Start(&'a BytesStart<'a>),
-    End(&'a BytesEnd<'a>),
 }
 
 pub(in crate::reader) fn get_elm_name(tag: &BytesTag) -> String {
     match tag {
         BytesTag::Start(tag) => String::from_utf8(tag.name().as_ref().to_vec()).unwrap(),
-        BytesTag::End(tag) => String::from_utf8(tag.name().as_ref().to_vec()).unwrap(),
     }
 }
 
@@ -39,3 +37,14 @@ pub fn get_last_vec_element(src: &Vec<String>) -> Result<String, Box<dyn Error>>
 pub fn string_vec(str_vec: Vec<&str>) -> Vec<String> {
     str_vec.iter().map(|x| x.to_string()).collect()
 }
+
+/// `/`区切りのタグのパスをタグ階層に変換します。
+/// 空の要素は無視します。
+/// 例: "tv/programme/title" -> vec!["tv".to_string(), "programme".to_string(), "title".to_string()]
+pub fn parse_tag_path(path: &str) -> Vec<String> {
+    path.split('/')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_string())
+        .collect()
+}
@@ -0,0 +1,775 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::BufRead;
+
+use quick_xml::events::Event;
+
+use super::discover::{DiscoveryReport, discover_with_sample_limit};
+use super::tag_value_type::TagValueType;
+use super::xml_read_config::XmlReadConfig;
+
+/// 設定の検証で見つかった問題を表します。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ValidationIssue {
+    /// グループ化の単位となるタグ階層が文書に存在しない
+    GroupHierarchyNotFound { tag_group_hierarchy: Vec<String> },
+    /// グループ化の単位となるタグ階層に`XmlTagReadConfig`がないため、グループが出力されない
+    GroupHierarchyNotConfigured { tag_group_hierarchy: Vec<String> },
+    /// 設定されたタグ階層が文書に存在しない
+    TagHierarchyNotFound { tag_hierarchy: Vec<String> },
+    /// 設定された属性がタグに存在しない
+    AttributeNotFound {
+        tag_hierarchy: Vec<String>,
+        name: String,
+    },
+    /// テキストを取得する設定のタグにテキストが存在しない
+    TextNotFound { tag_hierarchy: Vec<String> },
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::GroupHierarchyNotFound {
+                tag_group_hierarchy,
+            } => write!(
+                f,
+                "group hierarchy {} never occurs; no groups will be produced",
+                tag_group_hierarchy.join("/")
+            ),
+            ValidationIssue::GroupHierarchyNotConfigured {
+                tag_group_hierarchy,
+            } => write!(
+                f,
+                "group hierarchy {} has no tag read config; no groups will be produced",
+                tag_group_hierarchy.join("/")
+            ),
+            ValidationIssue::TagHierarchyNotFound { tag_hierarchy } => {
+                write!(f, "hierarchy {} never occurs", tag_hierarchy.join("/"))
+            }
+            ValidationIssue::AttributeNotFound {
+                tag_hierarchy,
+                name,
+            } => write!(
+                f,
+                "attribute @{} is never present on {}",
+                name,
+                tag_hierarchy.join("/")
+            ),
+            ValidationIssue::TextNotFound { tag_hierarchy } => {
+                write!(f, "{} never carries text", tag_hierarchy.join("/"))
+            }
+        }
+    }
+}
+
+/// 設定の検証結果です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// 見つかった問題のリスト
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// 問題が見つからなかったかどうかを確認します。
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 見つかった問題のリストを取得します。
+    pub fn get_issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// 検証の基準となる文書構造です。
+trait DocumentStructure {
+    /// タグ階層が存在するかどうか
+    fn has_hierarchy(&self, tag_hierarchy: &[String]) -> bool;
+    /// タグ階層の最後のタグに属性が存在するかどうか
+    fn has_attribute(&self, tag_hierarchy: &[String], name: &str) -> bool;
+    /// タグ階層の最後のタグにテキストが存在するかどうか
+    fn has_text(&self, tag_hierarchy: &[String]) -> bool;
+}
+
+impl DocumentStructure for DiscoveryReport {
+    fn has_hierarchy(&self, tag_hierarchy: &[String]) -> bool {
+        self.get(tag_hierarchy).is_some()
+    }
+
+    fn has_attribute(&self, tag_hierarchy: &[String], name: &str) -> bool {
+        self.get(tag_hierarchy)
+            .is_some_and(|summary| summary.get_attribute(name).is_some())
+    }
+
+    fn has_text(&self, tag_hierarchy: &[String]) -> bool {
+        self.get(tag_hierarchy)
+            .is_some_and(|summary| summary.has_text())
+    }
+}
+
+/// DTDの要素宣言と属性リスト宣言、またはXSDの要素・属性の宣言から収集した情報です。
+#[derive(Debug, Default)]
+struct DeclaredStructure {
+    /// 要素名をキー、(子要素名のリスト, 任意の子要素を許すかどうか, テキストを持てるかどうか)を値とするハッシュマップ
+    elements: HashMap<String, (HashSet<String>, bool, bool)>,
+    /// 要素名をキー、属性名のリストを値とするハッシュマップ
+    attributes: HashMap<String, HashSet<String>>,
+}
+
+impl DocumentStructure for DeclaredStructure {
+    fn has_hierarchy(&self, tag_hierarchy: &[String]) -> bool {
+        let Some(first) = tag_hierarchy.first() else {
+            return false;
+        };
+        if !self.elements.contains_key(first) {
+            return false;
+        }
+        tag_hierarchy.windows(2).all(|pair| {
+            self.elements
+                .get(&pair[0])
+                .is_some_and(|(children, any, _)| {
+                    (*any && self.elements.contains_key(&pair[1])) || children.contains(&pair[1])
+                })
+        })
+    }
+
+    fn has_attribute(&self, tag_hierarchy: &[String], name: &str) -> bool {
+        tag_hierarchy.last().is_some_and(|elm_name| {
+            self.attributes
+                .get(elm_name)
+                .is_some_and(|attributes| attributes.contains(name))
+        })
+    }
+
+    fn has_text(&self, tag_hierarchy: &[String]) -> bool {
+        tag_hierarchy.last().is_some_and(|elm_name| {
+            self.elements
+                .get(elm_name)
+                .is_some_and(|(_, _, has_text)| *has_text)
+        })
+    }
+}
+
+impl DeclaredStructure {
+    fn parse(dtd: &str) -> Result<Self, Box<dyn Error>> {
+        let mut structure = DeclaredStructure::default();
+        let mut rest = dtd;
+        while let Some(pos) = rest.find('<') {
+            rest = &rest[pos..];
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = match comment.find("-->") {
+                    Some(end) => &comment[end + 3..],
+                    None => "",
+                };
+                continue;
+            }
+            let end = Self::find_declaration_end(rest).ok_or_else(|| {
+                let s_err = format!("UNTERMINATED DTD DECLARATION. {:?}", rest);
+                std::io::Error::new(std::io::ErrorKind::InvalidData, s_err)
+            })?;
+            let declaration = &rest[..end];
+            rest = &rest[end + 1..];
+            if let Some(body) = declaration.strip_prefix("<!ELEMENT") {
+                structure.put_element(body)?;
+            } else if let Some(body) = declaration.strip_prefix("<!ATTLIST") {
+                structure.put_attribute_list(body)?;
+            }
+        }
+        Ok(structure)
+    }
+
+    /// 引用符の中を除いて、宣言の終わりの`>`の位置を探します。
+    fn find_declaration_end(s: &str) -> Option<usize> {
+        let mut quote: Option<char> = None;
+        for (i, c) in s.char_indices() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => (),
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c == '>' => return Some(i),
+                None => (),
+            }
+        }
+        None
+    }
+
+    fn put_element(&mut self, body: &str) -> Result<(), Box<dyn Error>> {
+        let body = body.trim();
+        let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+        let (name, content) = body.split_at(name_end);
+        if name.is_empty() {
+            return Err(format!("ELEMENT NAME IS EMPTY. <!ELEMENT{}>", body).into());
+        }
+        let content = content.trim();
+        let any = content == "ANY";
+        let has_text = any || content.contains("#PCDATA");
+        let children: HashSet<String> = content
+            .split(|c: char| "()|,?*+ \t\r\n".contains(c))
+            .filter(|x| !x.is_empty() && *x != "#PCDATA" && *x != "EMPTY" && *x != "ANY")
+            .map(|x| x.to_string())
+            .collect();
+        self.elements
+            .insert(name.to_string(), (children, any, has_text));
+        Ok(())
+    }
+
+    fn put_attribute_list(&mut self, body: &str) -> Result<(), Box<dyn Error>> {
+        let tokens = Self::tokenize(body);
+        let mut tokens = tokens.into_iter();
+        let elm_name = tokens
+            .next()
+            .ok_or_else(|| format!("ELEMENT NAME IS EMPTY. <!ATTLIST{}>", body))?;
+        let attributes = self.attributes.entry(elm_name).or_default();
+        while let Some(attr_name) = tokens.next() {
+            // 属性の型(NOTATIONの場合は列挙が続く)
+            if tokens.next().as_deref() == Some("NOTATION") {
+                tokens.next();
+            }
+            // 既定値の宣言(#FIXEDの場合は値が続く)
+            if tokens.next().as_deref() == Some("#FIXED") {
+                tokens.next();
+            }
+            attributes.insert(attr_name);
+        }
+        Ok(())
+    }
+
+    /// 属性リスト宣言を、名前・括弧で囲まれた列挙・引用符で囲まれた値に分割します。
+    fn tokenize(s: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut chars = s.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '(' {
+                let mut token = String::new();
+                for c in chars.by_ref() {
+                    token.push(c);
+                    if c == ')' {
+                        break;
+                    }
+                }
+                tokens.push(token);
+            } else if c == '"' || c == '\'' {
+                chars.next();
+                let token: String = chars.by_ref().take_while(|x| *x != c).collect();
+                tokens.push(token);
+            } else {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+        tokens
+    }
+}
+
+/// XSDの要素・名前付きの型などの、宣言の内容です。
+#[derive(Debug, Default)]
+struct XsdContent {
+    /// 子要素名のリスト
+    children: HashSet<String>,
+    /// 属性名のリスト
+    attributes: HashSet<String>,
+    /// 任意の子要素を許すかどうか(`xs:any`)
+    any: bool,
+    /// テキストを持てるかどうか
+    has_text: bool,
+    /// 型が定義されているかどうか(定義されていない要素は`xs:anyType`になる)
+    has_type: bool,
+    /// 内容を引き継ぐ型・グループ・属性グループの名前(`type`・`base`・`ref`)
+    bases: Vec<String>,
+}
+
+/// XSDの宣言の持ち主です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum XsdOwner {
+    /// 要素(`xs:element name="..."`)
+    Element(String),
+    /// 名前付きの型・グループ・属性グループ(`xs:complexType name="..."`など)
+    Type(String),
+    /// 属性(属性の型の宣言を、要素の内容に含めないため)
+    Attribute,
+}
+
+impl DeclaredStructure {
+    /// XSDの要素・属性の宣言から`DeclaredStructure`を作成します。
+    ///
+    /// 名前空間の接頭辞は無視し、名前付きの型・グループ・属性グループ・`xs:extension`の基底型の内容は、
+    /// それを参照する要素に引き継ぎます。
+    /// `xs:import`・`xs:include`で参照される、別のファイルのスキーマは読み込みません。
+    fn parse_xsd(xsd: &str) -> Result<Self, Box<dyn Error>> {
+        let mut contents: HashMap<XsdOwner, XsdContent> = HashMap::new();
+        // 開いているタグごとの、宣言の持ち主(持ち主にならないタグは`None`)
+        let mut owner_stack: Vec<Option<XsdOwner>> = Vec::new();
+        let mut reader = quick_xml::Reader::from_str(xsd);
+        loop {
+            let (start, is_empty) = match reader.read_event()? {
+                Event::Start(start) => (start, false),
+                Event::Empty(start) => (start, true),
+                Event::End(_) => {
+                    // 型が定義されていない要素は、任意の子要素とテキストを持てる
+                    if let Some(Some(owner @ XsdOwner::Element(_))) = owner_stack.pop() {
+                        let content = contents.entry(owner).or_default();
+                        if !content.has_type {
+                            content.any = true;
+                            content.has_text = true;
+                        }
+                    }
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+            let get_attribute = |name: &str| -> Result<Option<String>, Box<dyn Error>> {
+                match start.try_get_attribute(name)? {
+                    Some(attribute) => Ok(Some(local_name(&attribute.unescape_value()?))),
+                    None => Ok(None),
+                }
+            };
+            let current = owner_stack.iter().rev().find_map(|x| x.clone());
+            let is_top_level = owner_stack.len() == 1;
+            let mut owner: Option<XsdOwner> = None;
+            match start.local_name().as_ref() {
+                b"element" => {
+                    let name = get_attribute("name")?;
+                    let element_name = name.clone().or(get_attribute("ref")?);
+                    if let (Some(current), Some(element_name)) = (&current, &element_name) {
+                        contents
+                            .entry(current.clone())
+                            .or_default()
+                            .children
+                            .insert(element_name.clone());
+                    }
+                    if let Some(name) = name {
+                        let content = contents.entry(XsdOwner::Element(name.clone())).or_default();
+                        if let Some(type_name) = get_attribute("type")? {
+                            content.has_type = true;
+                            content.bases.push(type_name);
+                        }
+                        owner = Some(XsdOwner::Element(name));
+                    }
+                }
+                b"complexType" | b"simpleType" | b"group" | b"attributeGroup" => {
+                    let is_simple_type = start.local_name().as_ref() == b"simpleType";
+                    match (get_attribute("name")?, get_attribute("ref")?) {
+                        (Some(name), _) if is_top_level => {
+                            owner = Some(XsdOwner::Type(name));
+                        }
+                        (_, Some(ref_name)) => {
+                            if let Some(current) = &current {
+                                contents
+                                    .entry(current.clone())
+                                    .or_default()
+                                    .bases
+                                    .push(ref_name);
+                            }
+                        }
+                        _ => (),
+                    }
+                    let target = owner.clone().or(current);
+                    if let Some(target) = target {
+                        let content = contents.entry(target).or_default();
+                        content.has_type = true;
+                        if is_simple_type || get_attribute("mixed")?.as_deref() == Some("true") {
+                            content.has_text = true;
+                        }
+                    }
+                }
+                b"simpleContent" => {
+                    if let Some(current) = current {
+                        contents.entry(current).or_default().has_text = true;
+                    }
+                }
+                b"extension" | b"restriction" => {
+                    let base = get_attribute("base")?.filter(|x| x != "anyType");
+                    if let (Some(current), Some(base)) = (current, base) {
+                        contents.entry(current).or_default().bases.push(base);
+                    }
+                }
+                b"attribute" => {
+                    let name = get_attribute("name")?.or(get_attribute("ref")?);
+                    if let (Some(current), Some(name)) = (current, name) {
+                        contents.entry(current).or_default().attributes.insert(name);
+                    }
+                    owner = Some(XsdOwner::Attribute);
+                }
+                b"any" => {
+                    if let Some(current) = current {
+                        contents.entry(current).or_default().any = true;
+                    }
+                }
+                _ => (),
+            }
+            if is_empty {
+                // 空要素タグは、終了タグと同じく型が定義されていない要素を処理する
+                if let Some(XsdOwner::Element(name)) = &owner {
+                    let content = contents.entry(XsdOwner::Element(name.clone())).or_default();
+                    if !content.has_type {
+                        content.any = true;
+                        content.has_text = true;
+                    }
+                }
+            } else {
+                owner_stack.push(owner);
+            }
+        }
+
+        let mut structure = DeclaredStructure::default();
+        for owner in contents.keys() {
+            let XsdOwner::Element(name) = owner else {
+                continue;
+            };
+            let mut children = HashSet::new();
+            let mut attributes = HashSet::new();
+            let (mut any, mut has_text) = (false, false);
+            // 引き継ぐ型をたどる(循環していても1回ずつ)
+            let mut visited: HashSet<XsdOwner> = HashSet::new();
+            let mut pending = vec![owner.clone()];
+            while let Some(owner) = pending.pop() {
+                if !visited.insert(owner.clone()) {
+                    continue;
+                }
+                let Some(content) = contents.get(&owner) else {
+                    continue;
+                };
+                children.extend(content.children.iter().cloned());
+                attributes.extend(content.attributes.iter().cloned());
+                any |= content.any;
+                has_text |= content.has_text;
+                for base in &content.bases {
+                    let base_owner = XsdOwner::Type(base.clone());
+                    if contents.contains_key(&base_owner) {
+                        pending.push(base_owner);
+                    } else {
+                        // 定義されていない型は、組み込みの単純型(`xs:string`など)とする
+                        has_text = true;
+                    }
+                }
+            }
+            structure
+                .elements
+                .insert(name.clone(), (children, any, has_text));
+            structure.attributes.insert(name.clone(), attributes);
+        }
+        Ok(structure)
+    }
+}
+
+/// 名前空間の接頭辞を除いた名前を取得します。(`xs:string`であれば`string`)
+fn local_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_string()
+}
+
+fn validate(config: &XmlReadConfig, structure: &dyn DocumentStructure) -> ValidationReport {
+    let mut issues = Vec::new();
+    let tag_group_hierarchy = config.get_tag_group_hierarchy();
+    let tag_hierarchy_map = config.get_tag_hierarchy_map();
+    if !structure.has_hierarchy(&tag_group_hierarchy) {
+        issues.push(ValidationIssue::GroupHierarchyNotFound {
+            tag_group_hierarchy: tag_group_hierarchy.clone(),
+        });
+    }
+    if !tag_hierarchy_map.contains_key(&tag_group_hierarchy) {
+        issues.push(ValidationIssue::GroupHierarchyNotConfigured {
+            tag_group_hierarchy: tag_group_hierarchy.clone(),
+        });
+    }
+
+    // 結果が毎回同じ順序になるようにタグ階層で並べ替える
+    let mut tag_read_configs: Vec<_> = tag_hierarchy_map.into_values().collect();
+    tag_read_configs.sort_by_key(|x| x.get_tag_hierarchy());
+    for tag_read_config in tag_read_configs {
+        let tag_hierarchy = tag_read_config.get_tag_hierarchy();
+        if !structure.has_hierarchy(&tag_hierarchy) {
+            issues.push(ValidationIssue::TagHierarchyNotFound { tag_hierarchy });
+            continue;
+        }
+        let mut tag_value_names: Vec<_> = tag_read_config
+            .get_target_tag_value_names()
+            .into_iter()
+            .collect();
+        tag_value_names.sort();
+        for tag_value_name in tag_value_names {
+            match tag_value_name.get_value_type() {
+                TagValueType::TagText => {
+                    if !structure.has_text(&tag_hierarchy) {
+                        issues.push(ValidationIssue::TextNotFound {
+                            tag_hierarchy: tag_hierarchy.clone(),
+                        });
+                    }
+                }
+                // タグのXMLは、タグがあれば取得できる
+                TagValueType::InnerXml | TagValueType::OuterXml => (),
+                // コメント・処理命令は、存在しなくてもよい
+                TagValueType::Comment | TagValueType::ProcessingInstruction => (),
+                // すべての属性(`@*`)は、存在しなくてもよい
+                TagValueType::AttributeValue if tag_value_name.is_all_attributes() => (),
+                TagValueType::AttributeValue => {
+                    let name = tag_value_name.get_name();
+                    if !structure.has_attribute(&tag_hierarchy, &name) {
+                        issues.push(ValidationIssue::AttributeNotFound {
+                            tag_hierarchy: tag_hierarchy.clone(),
+                            name,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    ValidationReport { issues }
+}
+
+/// サンプル文書を読み込み、設定されたタグ階層・属性・テキストが文書に存在するかを検証します。
+///
+/// # 引数
+/// - `reader`: サンプル文書のリーダー
+/// - `config`: 検証する`XmlReadConfig`
+///
+/// # 戻り値
+/// - 検証結果(文書の読み込みに失敗した場合はエラー)
+pub fn validate_with_sample<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+    config: &XmlReadConfig,
+) -> Result<ValidationReport, Box<dyn Error>> {
+    let structure = discover_with_sample_limit(reader, 0)?;
+    Ok(validate(config, &structure))
+}
+
+/// DTDの`<!ELEMENT>`と`<!ATTLIST>`の宣言から、設定されたタグ階層・属性・テキストが文書に現れうるかを検証します。
+///
+/// パラメータ実体参照は展開しません。
+///
+/// # 引数
+/// - `dtd`: DTDの内容
+/// - `config`: 検証する`XmlReadConfig`
+///
+/// # 戻り値
+/// - 検証結果(DTDの宣言が閉じていない場合はエラー)
+pub fn validate_with_dtd(
+    dtd: &str,
+    config: &XmlReadConfig,
+) -> Result<ValidationReport, Box<dyn Error>> {
+    let structure = DeclaredStructure::parse(dtd)?;
+    Ok(validate(config, &structure))
+}
+
+/// XSD(XMLスキーマ)の要素・属性の宣言から、設定されたタグ階層・属性・テキストが文書に現れうるかを検証します。
+///
+/// 内容モデル(出現回数・順序など)は検証せず、要素・属性の名前と親子関係のみを使用します。
+/// `xs:import`・`xs:include`で参照される、別のファイルのスキーマは読み込みません。
+///
+/// # 引数
+/// - `xsd`: XSDの内容
+/// - `config`: 検証する`XmlReadConfig`
+///
+/// # 戻り値
+/// - 検証結果(XSDがXMLとして正しくない場合はエラー)
+pub fn validate_with_xsd(
+    xsd: &str,
+    config: &XmlReadConfig,
+) -> Result<ValidationReport, Box<dyn Error>> {
+    let structure = DeclaredStructure::parse_xsd(xsd)?;
+    Ok(validate(config, &structure))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::reader::{
+        util::string_vec,
+        xml_read_config::{TagValueName, XmlTagReadConfig},
+    };
+
+    const XML_FILE: &str = "test_xml/sample1.xml";
+
+    const DTD: &str = r#"
+        <!ELEMENT tv (channel*, programme*)>
+        <!ATTLIST tv generator-info-name CDATA #IMPLIED>
+        <!-- <!ELEMENT ignored (#PCDATA)> -->
+        <!ELEMENT programme (title+, desc*, category*)>
+        <!ATTLIST programme start CDATA #REQUIRED
+                            stop CDATA #IMPLIED
+                            clumpidx CDATA "0/1">
+        <!ELEMENT title (#PCDATA)>
+        <!ATTLIST title lang CDATA #IMPLIED>
+        <!ELEMENT desc (#PCDATA)>
+        <!ELEMENT category (#PCDATA)>
+        <!ATTLIST category lang (ja_JP|en) #FIXED "en">
+    "#;
+
+    fn programme_config() -> XmlReadConfig {
+        let programme = string_vec(vec!["tv", "programme"]);
+        let mut config = XmlReadConfig::new(programme.clone());
+        for (tag_hierarchy, values) in [
+            (
+                programme.clone(),
+                vec![
+                    TagValueName::new_attribute("start".to_string()),
+                    TagValueName::new_attribute("stat".to_string()),
+                ],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "title"]),
+                vec![
+                    TagValueName::new_tag_text(),
+                    TagValueName::new_attribute("lang".to_string()),
+                ],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "category"]),
+                vec![TagValueName::new_attribute("lang".to_string())],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "tilte"]),
+                vec![TagValueName::new_tag_text()],
+            ),
+            (
+                string_vec(vec!["tv", "programme", "video"]),
+                vec![TagValueName::new_tag_text()],
+            ),
+        ] {
+            config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy,
+                    HashSet::from_iter(values),
+                ))
+                .unwrap();
+        }
+        config
+    }
+
+    #[test_log::test]
+    fn test_validate_with_sample() {
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let report = validate_with_sample(&mut reader, &programme_config()).unwrap();
+        assert_eq!(
+            report.get_issues(),
+            &[
+                ValidationIssue::AttributeNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme"]),
+                    name: "stat".to_string(),
+                },
+                ValidationIssue::TagHierarchyNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "tilte"]),
+                },
+                ValidationIssue::TextNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "video"]),
+                },
+            ]
+        );
+
+        let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(
+            std::fs::File::open(XML_FILE).unwrap(),
+        ));
+        let report = validate_with_sample(
+            &mut reader,
+            &XmlReadConfig::new(string_vec(vec!["tv", "show"])),
+        )
+        .unwrap();
+        assert_eq!(report.get_issues().len(), 2);
+    }
+
+    #[test_log::test]
+    fn test_validate_with_xsd() {
+        let xsd = r#"<?xml version="1.0"?>
+            <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+              <xs:element name="tv">
+                <xs:complexType>
+                  <xs:sequence>
+                    <xs:element ref="programme" maxOccurs="unbounded"/>
+                  </xs:sequence>
+                  <xs:attribute name="generator-info-name" type="xs:string"/>
+                </xs:complexType>
+              </xs:element>
+              <xs:element name="programme" type="programmeType"/>
+              <xs:complexType name="programmeType">
+                <xs:sequence>
+                  <xs:element name="title" type="langText" maxOccurs="unbounded"/>
+                  <xs:element name="desc" type="langText" minOccurs="0"/>
+                  <xs:group ref="categories"/>
+                </xs:sequence>
+                <xs:attribute name="start" type="xs:string" use="required"/>
+                <xs:attribute name="stop">
+                  <xs:simpleType><xs:restriction base="xs:string"/></xs:simpleType>
+                </xs:attribute>
+              </xs:complexType>
+              <xs:group name="categories">
+                <xs:sequence>
+                  <xs:element name="category" type="langText" minOccurs="0"/>
+                  <xs:element name="icon"><xs:complexType/></xs:element>
+                </xs:sequence>
+              </xs:group>
+              <xs:complexType name="langText">
+                <xs:simpleContent>
+                  <xs:extension base="xs:string">
+                    <xs:attribute name="lang" type="xs:language"/>
+                  </xs:extension>
+                </xs:simpleContent>
+              </xs:complexType>
+            </xs:schema>"#;
+        let report = validate_with_xsd(xsd, &programme_config()).unwrap();
+        assert_eq!(
+            report.get_issues(),
+            &[
+                ValidationIssue::AttributeNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme"]),
+                    name: "stat".to_string(),
+                },
+                ValidationIssue::TagHierarchyNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "tilte"]),
+                },
+                ValidationIssue::TagHierarchyNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "video"]),
+                },
+            ]
+        );
+
+        let structure = DeclaredStructure::parse_xsd(xsd).unwrap();
+        let programme = string_vec(vec!["tv", "programme"]);
+        assert!(structure.has_attribute(&programme, "stop"));
+        assert!(!structure.has_text(&programme));
+        assert!(structure.has_hierarchy(&string_vec(vec!["tv", "programme", "category"])));
+        assert!(!structure.has_text(&string_vec(vec!["tv", "programme", "icon"])));
+    }
+
+    #[test_log::test]
+    fn test_validate_with_dtd() {
+        let report = validate_with_dtd(DTD, &programme_config()).unwrap();
+        assert_eq!(
+            report.get_issues(),
+            &[
+                ValidationIssue::AttributeNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme"]),
+                    name: "stat".to_string(),
+                },
+                ValidationIssue::TagHierarchyNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "tilte"]),
+                },
+                ValidationIssue::TagHierarchyNotFound {
+                    tag_hierarchy: string_vec(vec!["tv", "programme", "video"]),
+                },
+            ]
+        );
+    }
+}
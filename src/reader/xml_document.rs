@@ -0,0 +1,231 @@
+use super::output_tag_value::OutPutTagValue;
+use super::tag_value::TagValue;
+use super::tag_value_type::TagValueType;
+
+/// 文書全体に関する情報です。(`read_xml_document`・`read_xml_document_each`で取得)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct XmlDocumentInfo {
+    /// XML宣言の`version`(XML宣言がない場合は`None`)
+    version: Option<String>,
+    /// XML宣言の`encoding`(指定がない場合は`None`)
+    encoding: Option<String>,
+    /// XML宣言の`standalone`(指定がない場合は`None`)
+    standalone: Option<bool>,
+    /// 文書型宣言(`<!DOCTYPE ...>`がない場合は`None`)
+    doctype: Option<XmlDocType>,
+    /// 文書レベル(ルートタグの外)のコメント・処理命令のうち、設定で指定されたもの(文書の順)
+    document_values: Vec<TagValue>,
+}
+
+impl XmlDocumentInfo {
+    /// XML宣言の`version`を取得します。
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// XML宣言の`encoding`を取得します。(宣言に書かれたとおりの値で、大文字・小文字は変換しない)
+    pub fn get_encoding(&self) -> Option<&str> {
+        self.encoding.as_deref()
+    }
+
+    /// XML宣言の`standalone`を取得します。(`yes`であれば`true`、`no`であれば`false`)
+    pub fn get_standalone(&self) -> Option<bool> {
+        self.standalone
+    }
+
+    /// 文書型宣言を取得します。
+    pub fn get_doctype(&self) -> Option<&XmlDocType> {
+        self.doctype.as_ref()
+    }
+
+    /// 文書レベルのコメント・処理命令を文書の順に取得します。
+    pub fn get_document_values(&self) -> &[TagValue] {
+        &self.document_values
+    }
+
+    /// 文書レベルのコメントを文書の順に取得します。
+    pub fn get_comments(&self) -> Vec<&str> {
+        self.document_values
+            .iter()
+            .filter(|x| *x.get_value_type() == TagValueType::Comment)
+            .map(|x| x.get_value())
+            .collect()
+    }
+
+    /// 文書レベルの処理命令のうち、ターゲット名が一致するものの内容を文書の順に取得します。
+    pub fn get_processing_instructions(&self, target: &str) -> Vec<&str> {
+        self.document_values
+            .iter()
+            .filter(|x| {
+                *x.get_value_type() == TagValueType::ProcessingInstruction
+                    && x.get_tag_name() == target
+            })
+            .map(|x| x.get_value())
+            .collect()
+    }
+
+    /// XML宣言の内容を設定します。
+    pub(in crate::reader) fn set_declaration(
+        &mut self,
+        version: String,
+        encoding: Option<String>,
+        standalone: Option<bool>,
+    ) {
+        self.version = Some(version);
+        self.encoding = encoding;
+        self.standalone = standalone;
+    }
+
+    /// 文書型宣言を設定します。
+    pub(in crate::reader) fn set_doctype(&mut self, doctype: XmlDocType) {
+        self.doctype = Some(doctype);
+    }
+
+    /// 文書レベルのコメント・処理命令を追加します。
+    pub(in crate::reader) fn push_document_value(&mut self, tag_value: TagValue) {
+        self.document_values.push(tag_value);
+    }
+}
+
+/// 文書型宣言(`<!DOCTYPE tv SYSTEM "xmltv.dtd">`など)です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct XmlDocType {
+    /// ルートタグ名(`tv`など)
+    name: String,
+    /// 公開識別子(`PUBLIC "..."`の値)
+    public_id: Option<String>,
+    /// システム識別子(`SYSTEM "..."`・`PUBLIC "..." "..."`の2つ目の値)
+    system_id: Option<String>,
+    /// `<!DOCTYPE`と`>`の間の文字列(前後の空白を除く)
+    content: String,
+}
+
+impl XmlDocType {
+    /// `<!DOCTYPE`と`>`の間の文字列から`XmlDocType`を作成します。
+    ///
+    /// 内部サブセット(`[...]`)は解析せず、`content`にのみ残ります。
+    pub fn parse(content: &str) -> Self {
+        let content = content.trim();
+        let (name, rest) = split_token(content);
+        let (keyword, rest) = split_token(rest);
+        let (public_id, system_id) = match keyword {
+            "SYSTEM" => (None, split_quoted(rest).0),
+            "PUBLIC" => {
+                let (public_id, rest) = split_quoted(rest);
+                (public_id, split_quoted(rest).0)
+            }
+            _ => (None, None),
+        };
+        XmlDocType {
+            name: name.to_string(),
+            public_id,
+            system_id,
+            content: content.to_string(),
+        }
+    }
+
+    /// ルートタグ名を取得します。
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 公開識別子を取得します。
+    pub fn get_public_id(&self) -> Option<&str> {
+        self.public_id.as_deref()
+    }
+
+    /// システム識別子を取得します。
+    pub fn get_system_id(&self) -> Option<&str> {
+        self.system_id.as_deref()
+    }
+
+    /// `<!DOCTYPE`と`>`の間の文字列を取得します。
+    pub fn get_content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// 先頭の空白を除き、最初の区切り(空白・`[`)までとそれ以降に分けます。
+fn split_token(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    let end = s
+        .find(|c: char| c.is_whitespace() || c == '[')
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// 先頭の空白を除き、引用符(`"`・`'`)で囲まれた値とそれ以降に分けます。
+fn split_quoted(s: &str) -> (Option<String>, &str) {
+    let s = s.trim_start();
+    let Some(quote) = s.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+        return (None, s);
+    };
+    match s[1..].find(quote) {
+        Some(end) => (Some(s[1..end + 1].to_string()), &s[end + 2..]),
+        None => (None, s),
+    }
+}
+
+/// XML文書の読み込み結果です。(`read_xml_document`で取得)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct XmlDocument {
+    /// 文書全体に関する情報
+    info: XmlDocumentInfo,
+    /// グループごとの値(`read_xml`の結果と同じ)
+    groups: Vec<Vec<OutPutTagValue>>,
+}
+
+impl XmlDocument {
+    pub(in crate::reader) fn new(info: XmlDocumentInfo, groups: Vec<Vec<OutPutTagValue>>) -> Self {
+        XmlDocument { info, groups }
+    }
+
+    /// 文書全体に関する情報を取得します。
+    pub fn get_info(&self) -> &XmlDocumentInfo {
+        &self.info
+    }
+
+    /// グループごとの値を取得します。
+    pub fn get_groups(&self) -> &[Vec<OutPutTagValue>] {
+        &self.groups
+    }
+
+    /// 文書全体に関する情報と、グループごとの値に分けます。
+    pub fn into_parts(self) -> (XmlDocumentInfo, Vec<Vec<OutPutTagValue>>) {
+        (self.info, self.groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_parse_doctype() {
+        let doctype = XmlDocType::parse(r#" tv SYSTEM "xmltv.dtd" "#);
+        assert_eq!(doctype.get_name(), "tv");
+        assert_eq!(doctype.get_public_id(), None);
+        assert_eq!(doctype.get_system_id(), Some("xmltv.dtd"));
+        assert_eq!(doctype.get_content(), r#"tv SYSTEM "xmltv.dtd""#);
+
+        let doctype = XmlDocType::parse(
+            r#"html PUBLIC '-//W3C//DTD XHTML 1.0 Strict//EN' "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd""#,
+        );
+        assert_eq!(doctype.get_name(), "html");
+        assert_eq!(
+            doctype.get_public_id(),
+            Some("-//W3C//DTD XHTML 1.0 Strict//EN")
+        );
+        assert_eq!(
+            doctype.get_system_id(),
+            Some("http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd")
+        );
+
+        let doctype = XmlDocType::parse("note[<!ELEMENT note (#PCDATA)>]");
+        assert_eq!(doctype.get_name(), "note");
+        assert_eq!(doctype.get_system_id(), None);
+    }
+}
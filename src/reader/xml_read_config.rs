@@ -1,12 +1,19 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
+use regex::Regex;
+
+use super::tag_value::TagValue;
 use super::tag_value_type::TagValueType;
+use super::typed_value::{TypedValue, ValueConversionError, ValueKind};
 
 /// `TagValueName`は、XMLタグの値（属性またはテキスト）を表します。
 ///
 /// この構造体は、XMLタグの属性値またはテキスト値を表現するために使用されます。
 /// 属性の場合は名前を持ち、テキスト値の場合は名前は空文字列になります。
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TagValueName {
     /// 種類（属性値またはタグテキスト）
     value_type: TagValueType,
@@ -42,6 +49,78 @@ impl TagValueName {
         }
     }
 
+    /// タグの内側のXMLを表す新しい`TagValueName`を作成します。(文字列表現は`inner_xml`)
+    ///
+    /// 開始タグと終了タグの間のマークアップを、文書に書かれたとおりに取得します。
+    pub fn new_inner_xml() -> Self {
+        TagValueName {
+            value_type: TagValueType::InnerXml,
+            name: String::new(),
+        }
+    }
+
+    /// タグ全体のXMLを表す新しい`TagValueName`を作成します。(文字列表現は`outer_xml`)
+    ///
+    /// 開始タグから終了タグまでのマークアップを、文書に書かれたとおりに取得します。
+    pub fn new_outer_xml() -> Self {
+        TagValueName {
+            value_type: TagValueType::OuterXml,
+            name: String::new(),
+        }
+    }
+
+    /// タグのXMLそのもの(`inner_xml`または`outer_xml`)を表すかどうかを確認します。
+    pub fn is_raw_xml(&self) -> bool {
+        matches!(
+            self.value_type,
+            TagValueType::InnerXml | TagValueType::OuterXml
+        )
+    }
+
+    /// コメントを表す新しい`TagValueName`を作成します。(文字列表現は`comment`)
+    ///
+    /// タグの直下(文書レベルの場合はルートタグの外)にあるコメントを取得します。
+    pub fn new_comment() -> Self {
+        TagValueName {
+            value_type: TagValueType::Comment,
+            name: String::new(),
+        }
+    }
+
+    /// 処理命令を表す新しい`TagValueName`を作成します。(文字列表現は`?ターゲット名`、例: `?xml-stylesheet`)
+    ///
+    /// タグの直下(文書レベルの場合はルートタグの外)にある処理命令を取得します。
+    /// ターゲット名に`*`を指定すると、すべての処理命令を取得します。
+    ///
+    /// # 引数
+    /// - `target`: 処理命令のターゲット名
+    pub fn new_processing_instruction(target: String) -> Self {
+        TagValueName {
+            value_type: TagValueType::ProcessingInstruction,
+            name: target,
+        }
+    }
+
+    /// コメントまたは処理命令を表すかどうかを確認します。
+    pub fn is_comment_or_processing_instruction(&self) -> bool {
+        matches!(
+            self.value_type,
+            TagValueType::Comment | TagValueType::ProcessingInstruction
+        )
+    }
+
+    /// すべての属性を表す新しい`TagValueName`を作成します。(文字列表現は`@*`)
+    ///
+    /// 取得する属性は、`XmlTagReadConfig`の除外リストと属性名のパターンで絞り込めます。
+    pub fn new_all_attributes() -> Self {
+        TagValueName::new_attribute(ALL_NAMES.to_string())
+    }
+
+    /// すべての属性を表すかどうかを確認します。
+    pub fn is_all_attributes(&self) -> bool {
+        self.value_type == TagValueType::AttributeValue && self.name == ALL_NAMES
+    }
+
     /// 名前を取得します。
     ///
     /// # 戻り値
@@ -57,17 +136,160 @@ impl TagValueName {
     pub fn get_value_type(&self) -> TagValueType {
         self.value_type.clone()
     }
+
+    /// 取得した値がこの`TagValueName`に該当するかどうかを確認します。
+    ///
+    /// # 引数
+    /// - `tag_value`: 確認する`TagValue`
+    ///
+    /// # 戻り値
+    /// - タグテキスト・XML・コメントの場合は、値が同じ種類であれば`true`
+    /// - 処理命令の場合は、値が同じターゲット名の処理命令であれば`true`(`?*`の場合は、値が処理命令であれば`true`)
+    /// - 属性値の場合は、値が同じ名前の属性値であれば`true`(すべての属性の場合は、値が属性値であれば`true`)
+    pub fn is_match(&self, tag_value: &TagValue) -> bool {
+        self.is_match_type_and_name(tag_value.get_value_type(), tag_value.get_tag_name())
+    }
+
+    /// 値の種類と名前が、この項目に該当するかどうかを確認します。(`is_match`を参照)
+    pub fn is_match_type_and_name(&self, value_type: &TagValueType, name: &str) -> bool {
+        match self.value_type {
+            TagValueType::AttributeValue => {
+                *value_type == TagValueType::AttributeValue
+                    && (self.is_all_attributes() || name == self.name)
+            }
+            TagValueType::ProcessingInstruction => {
+                *value_type == TagValueType::ProcessingInstruction
+                    && (self.name == ALL_NAMES || name == self.name)
+            }
+            _ => *value_type == self.value_type,
+        }
+    }
+}
+
+/// すべての属性・処理命令を表す名前です。
+const ALL_NAMES: &str = "*";
+
+/// `TagValueName`を文字列で表します。
+///
+/// - タグのテキスト: `text`
+/// - 属性: `@属性名`(例: `@start`)
+/// - すべての属性: `@*`
+/// - タグの内側のXML: `inner_xml`
+/// - タグ全体のXML: `outer_xml`
+/// - コメント: `comment`
+/// - 処理命令: `?ターゲット名`(例: `?xml-stylesheet`)、すべての処理命令: `?*`
+impl Display for TagValueName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value_type {
+            TagValueType::TagText => f.write_str("text"),
+            TagValueType::AttributeValue => write!(f, "@{}", self.name),
+            TagValueType::InnerXml => f.write_str("inner_xml"),
+            TagValueType::OuterXml => f.write_str("outer_xml"),
+            TagValueType::Comment => f.write_str("comment"),
+            TagValueType::ProcessingInstruction => write!(f, "?{}", self.name),
+        }
+    }
+}
+
+/// 文字列から`TagValueName`を作成します。(書式は`Display`と同じ)
+impl FromStr for TagValueName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "text" => return Ok(TagValueName::new_tag_text()),
+            "inner_xml" => return Ok(TagValueName::new_inner_xml()),
+            "outer_xml" => return Ok(TagValueName::new_outer_xml()),
+            "comment" => return Ok(TagValueName::new_comment()),
+            _ => (),
+        }
+        if let Some(target) = s.strip_prefix('?') {
+            if target.trim().is_empty() {
+                return Err(format!("processing instruction target is empty in {:?}", s));
+            }
+            return Ok(TagValueName::new_processing_instruction(
+                target.trim().to_string(),
+            ));
+        }
+        match s.strip_prefix('@') {
+            Some(name) if !name.trim().is_empty() => {
+                Ok(TagValueName::new_attribute(name.trim().to_string()))
+            }
+            Some(_) => Err(format!("attribute name is empty in {:?}", s)),
+            None => Err(format!(
+                "unknown tag value name {:?} (expected \"text\", \"inner_xml\", \"outer_xml\", \"comment\", \"@attribute\" or \"?target\")",
+                s
+            )),
+        }
+    }
+}
+
+/// 取得する属性名のパターン(正規表現)です。
+///
+/// 比較とハッシュは正規表現の文字列で行います。
+#[derive(Debug, Clone)]
+pub struct AttributeNamePattern(Regex);
+
+impl AttributeNamePattern {
+    /// 正規表現から新しい`AttributeNamePattern`を作成します。
+    ///
+    /// # 戻り値
+    /// - `Err(String)`: 正規表現が正しくない場合、エラーメッセージを含む
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern)
+            .map(AttributeNamePattern)
+            .map_err(|e| format!("invalid attribute pattern {:?}: {}", pattern, e))
+    }
+
+    /// 正規表現の文字列を取得します。
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// 属性名がパターンに一致するかどうかを確認します。
+    pub fn is_match(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+impl PartialEq for AttributeNamePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for AttributeNamePattern {}
+
+impl Hash for AttributeNamePattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
 }
 
 /// `XmlTagReadConfig`は、XMLタグの読み取り設定を表します。
 ///
 /// この構造体は、特定のXMLタグ階層に関連付けられた読み取り設定を保持します。
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "super::config_file::XmlTagReadConfigFile",
+        into = "super::config_file::XmlTagReadConfigFile"
+    )
+)]
 pub struct XmlTagReadConfig {
     /// タグ階層（例: `["root", "child", "subchild"]`）
     tag_hierarchy: Vec<String>,
     /// タグ階層の最後のタグから取得する項目のリスト（重複しないこと）
     target_tag_value_names: Vec<TagValueName>,
+    /// 項目ごとの値の種類（宣言されていない項目は`ValueKind::String`）
+    value_kinds: BTreeMap<TagValueName, ValueKind>,
+    /// すべての属性(`@*`)を取得する場合に除外する属性名
+    excluded_attributes: BTreeSet<String>,
+    /// すべての属性(`@*`)を取得する場合に、属性名が一致する属性だけを取得するパターン
+    attribute_name_pattern: Option<AttributeNamePattern>,
 }
 
 impl XmlTagReadConfig {
@@ -81,9 +303,16 @@ impl XmlTagReadConfig {
     /// - 新しい`XmlTagReadConfig`インスタンス
     ///
     pub fn new(tag_hierarchy: Vec<String>, target_tag_value_names: HashSet<TagValueName>) -> Self {
+        // 比較結果が HashSet の順序に左右されないように並べ替えておく
+        let mut target_tag_value_names: Vec<TagValueName> =
+            target_tag_value_names.into_iter().collect();
+        target_tag_value_names.sort();
         XmlTagReadConfig {
             tag_hierarchy,
-            target_tag_value_names: target_tag_value_names.into_iter().collect(),
+            target_tag_value_names,
+            value_kinds: BTreeMap::new(),
+            excluded_attributes: BTreeSet::new(),
+            attribute_name_pattern: None,
         }
     }
 
@@ -100,12 +329,161 @@ impl XmlTagReadConfig {
     /// # 戻り値
     /// - タグ階層の最後のタグから取得する項目のリスト（`HashSet<TagValueName>`）
     pub fn get_target_tag_value_names(&self) -> HashSet<TagValueName> {
-        let src_vec = self.target_tag_value_names.clone();
-        let mut target_set = HashSet::new();
-        for tag_value_name in src_vec {
-            target_set.insert(tag_value_name.clone());
+        self.target_tag_value_names.iter().cloned().collect()
+    }
+
+    /// 項目が取得対象に含まれているかどうかを確認します。(`get_target_tag_value_names`と異なり、複製しない)
+    pub fn contains_target_tag_value_name(&self, tag_value_name: &TagValueName) -> bool {
+        self.target_tag_value_names
+            .binary_search(tag_value_name)
+            .is_ok()
+    }
+
+    /// 取得した値が、取得対象の項目のいずれかに該当するかどうかを確認します。(ワイルドカードを含む)
+    pub fn is_target_tag_value(&self, tag_value: &TagValue) -> bool {
+        self.is_target_type_and_name(tag_value.get_value_type(), tag_value.get_tag_name())
+    }
+
+    /// 値の種類と名前が、取得対象の項目のいずれかに該当するかどうかを確認します。(ワイルドカードを含む)
+    pub fn is_target_type_and_name(&self, value_type: &TagValueType, name: &str) -> bool {
+        self.target_tag_value_names
+            .iter()
+            .any(|tag_value_name| tag_value_name.is_match_type_and_name(value_type, name))
+    }
+
+    /// タグ階層を複製せずに取得します。
+    pub(in crate::reader) fn get_tag_hierarchy_ref(&self) -> &[String] {
+        &self.tag_hierarchy
+    }
+
+    /// 項目の値の種類を宣言します。
+    ///
+    /// # 引数
+    /// - `tag_value_name`: 取得する項目（このタグ階層の取得対象であること）
+    /// - `value_kind`: 値の種類
+    ///
+    /// # 戻り値
+    /// - `Ok(())`: 正常に設定された場合
+    /// - `Err(String)`: 取得対象ではない項目の場合、エラーメッセージを含む
+    pub fn set_value_kind(
+        &mut self,
+        tag_value_name: TagValueName,
+        value_kind: ValueKind,
+    ) -> Result<(), String> {
+        if !self.target_tag_value_names.contains(&tag_value_name) {
+            return Err(format!(
+                "{} is not a target of tag_hierarchy {:?}",
+                tag_value_name, self.tag_hierarchy
+            ));
+        }
+        if value_kind == ValueKind::String {
+            self.value_kinds.remove(&tag_value_name);
+        } else {
+            self.value_kinds.insert(tag_value_name, value_kind);
+        }
+        Ok(())
+    }
+
+    /// 項目の値の種類を取得します。
+    ///
+    /// # 戻り値
+    /// - 宣言された種類（属性の種類が宣言されていない場合は`@*`の種類、いずれもない場合は`ValueKind::String`）
+    pub fn get_value_kind(&self, tag_value_name: &TagValueName) -> ValueKind {
+        let mut value_kind = self.value_kinds.get(tag_value_name);
+        if value_kind.is_none() && tag_value_name.get_value_type() == TagValueType::AttributeValue {
+            value_kind = self.value_kinds.get(&TagValueName::new_all_attributes());
+        }
+        value_kind.cloned().unwrap_or_default()
+    }
+
+    /// 宣言された値の種類をすべて取得します。
+    pub fn get_value_kinds(&self) -> BTreeMap<TagValueName, ValueKind> {
+        self.value_kinds.clone()
+    }
+
+    /// タグのXMLそのもの(`inner_xml`または`outer_xml`)を取得するかどうかを確認します。
+    pub fn has_raw_xml_target(&self) -> bool {
+        self.target_tag_value_names
+            .iter()
+            .any(|tag_value_name| tag_value_name.is_raw_xml())
+    }
+
+    /// すべての属性(`@*`)を取得する場合に除外する属性名を設定します。
+    ///
+    /// # 戻り値
+    /// - `Ok(())`: 正常に設定された場合
+    /// - `Err(String)`: 取得対象に`@*`がない場合、エラーメッセージを含む
+    pub fn set_excluded_attributes(
+        &mut self,
+        excluded_attributes: BTreeSet<String>,
+    ) -> Result<(), String> {
+        if !excluded_attributes.is_empty() {
+            self.check_all_attributes("excluded attributes")?;
+        }
+        self.excluded_attributes = excluded_attributes;
+        Ok(())
+    }
+
+    /// すべての属性(`@*`)を取得する場合に除外する属性名を取得します。
+    pub fn get_excluded_attributes(&self) -> BTreeSet<String> {
+        self.excluded_attributes.clone()
+    }
+
+    /// すべての属性(`@*`)を取得する場合に、属性名を絞り込むパターンを設定します。(`None`の場合は絞り込まない)
+    ///
+    /// # 戻り値
+    /// - `Ok(())`: 正常に設定された場合
+    /// - `Err(String)`: 取得対象に`@*`がない場合、エラーメッセージを含む
+    pub fn set_attribute_name_pattern(
+        &mut self,
+        attribute_name_pattern: Option<AttributeNamePattern>,
+    ) -> Result<(), String> {
+        if attribute_name_pattern.is_some() {
+            self.check_all_attributes("attribute pattern")?;
+        }
+        self.attribute_name_pattern = attribute_name_pattern;
+        Ok(())
+    }
+
+    /// すべての属性(`@*`)を取得する場合に、属性名を絞り込むパターンを取得します。
+    pub fn get_attribute_name_pattern(&self) -> Option<&AttributeNamePattern> {
+        self.attribute_name_pattern.as_ref()
+    }
+
+    /// 属性が取得対象かどうかを確認します。
+    ///
+    /// # 戻り値
+    /// - 属性名が取得対象に含まれている場合は`true`
+    /// - 取得対象に`@*`がある場合は、除外リストになく、パターンに一致すれば`true`
+    pub fn is_target_attribute(&self, name: &str) -> bool {
+        self.target_tag_value_names.iter().any(|tag_value_name| {
+            if tag_value_name.get_value_type() != TagValueType::AttributeValue {
+                false
+            } else if tag_value_name.is_all_attributes() {
+                !self.excluded_attributes.contains(name)
+                    && self
+                        .attribute_name_pattern
+                        .as_ref()
+                        .is_none_or(|pattern| pattern.is_match(name))
+            } else {
+                tag_value_name.name == name
+            }
+        })
+    }
+
+    /// 取得対象に`@*`があることを確認します。
+    fn check_all_attributes(&self, setting: &str) -> Result<(), String> {
+        if self
+            .target_tag_value_names
+            .contains(&TagValueName::new_all_attributes())
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} of tag_hierarchy {:?} is only valid with @*",
+                setting, self.tag_hierarchy
+            ))
         }
-        target_set
     }
 }
 
@@ -113,11 +491,21 @@ impl XmlTagReadConfig {
 ///
 /// この構造体は、複数のタグ階層に関連付けられた読み取り設定を管理します。
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "super::config_file::XmlReadConfigFile",
+        into = "super::config_file::XmlReadConfigFile"
+    )
+)]
 pub struct XmlReadConfig {
     /// タグ階層をキー、XMLタグ読み取り設定を値とするハッシュマップ
     tag_hierarchy_map: HashMap<Vec<String>, XmlTagReadConfig>,
     /// グループ化の単位となるタグ階層（タグ階層の一部であること）
     tag_group_hierarchy: Vec<String>,
+    /// 文書レベル(ルートタグの外)から取得する項目のリスト(コメント・処理命令のみ)
+    document_tag_value_names: HashSet<TagValueName>,
 }
 
 impl XmlReadConfig {
@@ -132,7 +520,57 @@ impl XmlReadConfig {
         XmlReadConfig {
             tag_hierarchy_map: HashMap::new(),
             tag_group_hierarchy: tag_group_hierarchy.clone(),
+            document_tag_value_names: HashSet::new(),
+        }
+    }
+
+    /// 文書レベル(ルートタグの外)から取得する項目を設定します。
+    ///
+    /// 取得した値は`read_xml_document`の結果(`XmlDocumentInfo`)に含まれます。
+    ///
+    /// # 引数
+    /// - `document_tag_value_names`: 取得する項目(コメント・処理命令のみ)
+    ///
+    /// # 戻り値
+    /// - `Ok(())`: 正常に設定された場合
+    /// - `Err(String)`: コメント・処理命令以外の項目がある場合、エラーメッセージを含む
+    pub fn set_document_tag_value_names(
+        &mut self,
+        document_tag_value_names: HashSet<TagValueName>,
+    ) -> Result<(), String> {
+        if let Some(tag_value_name) = document_tag_value_names
+            .iter()
+            .find(|x| !x.is_comment_or_processing_instruction())
+        {
+            return Err(format!(
+                "{} cannot be read at the document level (only comment and ?target)",
+                tag_value_name
+            ));
         }
+        self.document_tag_value_names = document_tag_value_names;
+        Ok(())
+    }
+
+    /// 文書レベル(ルートタグの外)から取得する項目を取得します。
+    pub fn get_document_tag_value_names(&self) -> HashSet<TagValueName> {
+        self.document_tag_value_names.clone()
+    }
+
+    /// 取得した値が、文書レベルから取得する項目のいずれかに該当するかどうかを確認します。
+    pub fn is_document_tag_value(&self, tag_value: &TagValue) -> bool {
+        self.document_tag_value_names
+            .iter()
+            .any(|tag_value_name| tag_value_name.is_match(tag_value))
+    }
+
+    /// タグ階層のXMLタグ読み取り設定を、複製せずに取得します。
+    pub fn get_tag_read_config(&self, tag_hierarchy: &[String]) -> Option<&XmlTagReadConfig> {
+        self.tag_hierarchy_map.get(tag_hierarchy)
+    }
+
+    /// XMLタグ読み取り設定を、複製せずにすべて取得します。(順序は不定)
+    pub fn get_tag_read_configs(&self) -> impl Iterator<Item = &XmlTagReadConfig> {
+        self.tag_hierarchy_map.values()
     }
 
     /// タグ階層をキー、XMLタグ読み取り設定を値とするハッシュマップを取得します。
@@ -176,6 +614,29 @@ impl XmlReadConfig {
         Ok(())
     }
 
+    /// 取得した値を、宣言された種類に変換します。
+    ///
+    /// # 引数
+    /// - `tag_hierarchy`: 値を取得したタグ階層
+    /// - `tag_value`: 変換する値
+    ///
+    /// # 戻り値
+    /// - `Ok(TypedValue)`: 変換できた場合（種類が宣言されていない場合は`TypedValue::String`）
+    /// - `Err(ValueConversionError)`: 変換できなかった場合、タグ階層と値を含む
+    pub fn convert_tag_value(
+        &self,
+        tag_hierarchy: &[String],
+        tag_value: &TagValue,
+    ) -> Result<TypedValue, ValueConversionError> {
+        let value_kind = match self.tag_hierarchy_map.get(tag_hierarchy) {
+            Some(config) => config.get_value_kind(&tag_value.get_tag_value_name()),
+            None => ValueKind::String,
+        };
+        tag_value
+            .get_typed_value(&value_kind)
+            .map_err(|e| e.with_tag_hierarchy(tag_hierarchy))
+    }
+
     /// タグ階層の一部であることを確認するためのヘルパー関数です。
     ///
     /// # 引数
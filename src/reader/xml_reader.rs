@@ -1,100 +1,347 @@
+use std::borrow::Cow;
 use std::error::Error;
+use std::io::BufRead;
 
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 
 use super::{
     hierarchy_tag_store::TagHierarchyStore,
     output_tag_value::OutPutTagValue,
-    util::{BytesTag, get_elm_name, get_last_vec_element},
-    xml_read_config::{TagValueName, XmlReadConfig},
+    raw_xml_capture::{RawXmlCapture, raw_event_bytes},
+    read_state::{ReadState, StartedTag, get_lang_attributes},
+    tag_value::TagValue,
+    tag_value_type::TagValueType,
+    util::{BytesTag, get_elm_name},
+    xml_document::{XmlDocType, XmlDocument, XmlDocumentInfo},
+    xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig},
 };
 
-pub fn read_xml(
-    reader: &mut quick_xml::Reader<std::io::BufReader<std::fs::File>>,
+/// XMLを読み込み、設定に従って取得した値をグループごとにまとめて返します。
+pub fn read_xml<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
     read_config: &XmlReadConfig,
 ) -> Result<Vec<Vec<OutPutTagValue>>, Box<dyn Error>> {
+    let mut out_put_values: Vec<Vec<OutPutTagValue>> = Vec::new();
+    read_xml_each(reader, read_config, |out_put_value| {
+        out_put_values.push(out_put_value);
+        Ok(())
+    })?;
+    Ok(out_put_values)
+}
+
+/// XMLを読み込み、グループが1つ完成するたびに`on_group`を呼び出します。
+///
+/// 結果をすべてメモリに保持しないため、大きなファイルを順次処理する場合に使用します。
+/// `on_group`がエラーを返した場合は、読み込みを中止してそのエラーを返します。
+pub fn read_xml_each<R, F>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    on_group: F,
+) -> Result<(), Box<dyn Error>>
+where
+    R: BufRead,
+    F: FnMut(Vec<OutPutTagValue>) -> Result<(), Box<dyn Error>>,
+{
+    read_xml_document_each(reader, read_config, on_group)?;
+    Ok(())
+}
+
+/// XMLを読み込み、グループごとの値と文書全体に関する情報を返します。
+///
+/// XML宣言(`version`・`encoding`・`standalone`)と文書型宣言は、設定によらず取得します。
+/// 文書レベル(ルートタグの外)のコメント・処理命令は、
+/// `XmlReadConfig::set_document_tag_value_names`で指定したものを取得します。
+pub fn read_xml_document<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+) -> Result<XmlDocument, Box<dyn Error>> {
+    let mut groups: Vec<Vec<OutPutTagValue>> = Vec::new();
+    let info = read_xml_document_each(reader, read_config, |group| {
+        groups.push(group);
+        Ok(())
+    })?;
+    Ok(XmlDocument::new(info, groups))
+}
+
+/// XMLを読み込み、グループが1つ完成するたびに`on_group`を呼び出し、最後に文書全体に関する情報を返します。
+///
+/// `read_xml_each`と同じく、結果をすべてメモリに保持しません。
+pub fn read_xml_document_each<R, F>(
+    reader: &mut quick_xml::Reader<R>,
+    read_config: &XmlReadConfig,
+    mut on_group: F,
+) -> Result<XmlDocumentInfo, Box<dyn Error>>
+where
+    R: BufRead,
+    F: FnMut(Vec<OutPutTagValue>) -> Result<(), Box<dyn Error>>,
+{
     let read_config: &XmlReadConfig = read_config;
+    let mut document_info = XmlDocumentInfo::default();
 
     let mut buf = Vec::new();
-    let mut current_tag_hierarchy: Vec<String> = Vec::new();
+    // 開いているタグのタグ階層・言語・順番など
+    let mut state: ReadState<String, String> = ReadState::new(read_config);
     let mut tag_store: TagHierarchyStore = TagHierarchyStore::new();
 
     let mut out_put_value: Vec<OutPutTagValue> = Vec::new();
-    let mut out_put_values: Vec<Vec<OutPutTagValue>> = Vec::new();
+    // `inner_xml`・`outer_xml`を取得するためのバイト列
+    let mut raw_xml_capture = RawXmlCapture::new(read_config);
 
     loop {
-        match reader.read_event_into(&mut buf) {
+        let position = reader.buffer_position();
+        let step = match reader.read_event_into(&mut buf) {
             Err(e) => return Err(Box::new(e)),
             Ok(Event::Eof) => break, // ファイルの終端まできたら処理を終了する
 
             // 開始イベント
             Ok(Event::Start(start)) => {
-                let elm_name = get_elm_name(&BytesTag::Start(&start));
-                let last_of_current_tag_hierarchy =
-                    get_last_vec_element(&current_tag_hierarchy).unwrap_or_default();
-                if elm_name != last_of_current_tag_hierarchy {
-                    current_tag_hierarchy.push(elm_name.clone());
-                }
-                if let Some(out_put_tag_value) = tag_store.get_mut(&current_tag_hierarchy) {
-                    out_put_tag_value.put_selected_tag_attribute_from_start_tag(
-                        &current_tag_hierarchy,
-                        &read_config.get_tag_hierarchy_map(),
-                        &start,
-                    )?;
-                }
+                start_tag(&mut state, &mut tag_store, &start, false)?;
+                EventStep::Start
             }
 
-            // 終了イベント
+            // 終了イベント(タグの終了は、XMLを取得した後に行う)
             Ok(Event::End(end)) => {
-                let elm_name = get_elm_name(&BytesTag::End(&end));
-                let last_of_current_tag_hierarchy = get_last_vec_element(&current_tag_hierarchy)?;
-                if last_of_current_tag_hierarchy == elm_name {
-                    if read_config
-                        .get_tag_hierarchy_map()
-                        .contains_key(&current_tag_hierarchy)
-                    {
-                        if let Some(out_put_tag_value) = tag_store.get(&current_tag_hierarchy) {
-                            out_put_value.push(out_put_tag_value.clone());
-                            tag_store.remove(&current_tag_hierarchy);
-                        }
-                        let tag_group_hierarchy = read_config.get_tag_group_hierarchy();
-                        if current_tag_hierarchy == tag_group_hierarchy {
-                            out_put_values.push(out_put_value.clone());
-                            out_put_value.clear();
-                        }
-                    }
-                    current_tag_hierarchy.pop();
-                } else {
-                    let s = format!(
-                        "tags are mismatched! current_tag_hierarchy : {:?} , elm_name : {:?} !!",
-                        current_tag_hierarchy, elm_name
-                    );
-                    return Err(s.into());
-                }
+                state.check_end_tag(end.name().as_ref())?;
+                EventStep::End
+            }
+
+            // 空要素イベント(`<icon src="..."/>`など)は、開始と終了が続けて来たものとして扱う
+            Ok(Event::Empty(start)) => {
+                start_tag(&mut state, &mut tag_store, &start, true)?;
+                EventStep::Empty
             }
 
             // テキストイベント
             Ok(Event::Text(e)) => {
-                let tag_hierarchy_map = read_config.get_tag_hierarchy_map();
-                if let Some(config) = tag_hierarchy_map.get(&current_tag_hierarchy) {
-                    let values_list = config.get_target_tag_value_names();
-                    let text_value_name = TagValueName::new_tag_text();
-                    if values_list.contains(&text_value_name) {
-                        let now_text = e.unescape()?.into_owned();
-                        if let Some(out_put_tag_value) = tag_store.get_mut(&current_tag_hierarchy) {
-                            out_put_tag_value
-                                .put_tag_text_value(&current_tag_hierarchy, now_text)?;
-                        }
+                if state.is_text_target() {
+                    let now_text = e.unescape()?.into_owned();
+                    let current_tag_hierarchy = state.get_tag_hierarchy();
+                    if let Some(out_put_tag_value) = tag_store.get_mut(current_tag_hierarchy) {
+                        out_put_tag_value.put_tag_text_value(current_tag_hierarchy, now_text)?;
                     }
                 }
+                EventStep::Text
             }
 
-            // その他のイベントは何もしない
-            _ => (),
+            // コメントイベント
+            Ok(Event::Comment(e)) => {
+                let comment = reader.decoder().decode(&e)?.into_owned();
+                put_comment_or_processing_instruction(
+                    state.get_tag_hierarchy(),
+                    read_config,
+                    state.get_config(),
+                    &mut tag_store,
+                    &mut document_info,
+                    TagValue::new(TagValueType::Comment, String::new(), comment),
+                )?;
+                EventStep::Other
+            }
+
+            // 処理命令イベント(`<?xml-stylesheet href="..."?>`など。XML宣言は含まない)
+            Ok(Event::PI(e)) => {
+                let decoder = reader.decoder();
+                let target = decoder.decode(e.target())?.into_owned();
+                let content = decoder.decode(e.content())?.trim().to_string();
+                put_comment_or_processing_instruction(
+                    state.get_tag_hierarchy(),
+                    read_config,
+                    state.get_config(),
+                    &mut tag_store,
+                    &mut document_info,
+                    TagValue::new(TagValueType::ProcessingInstruction, target, content),
+                )?;
+                EventStep::Other
+            }
+
+            // XML宣言イベント
+            Ok(Event::Decl(e)) => {
+                let decoder = reader.decoder();
+                let version = decoder.decode(&e.version()?)?.into_owned();
+                let encoding = match e.encoding() {
+                    Some(encoding) => Some(decoder.decode(&encoding?)?.into_owned()),
+                    None => None,
+                };
+                let standalone = match e.standalone() {
+                    Some(standalone) => Some(decoder.decode(&standalone?)?.as_ref() == "yes"),
+                    None => None,
+                };
+                document_info.set_declaration(version, encoding, standalone);
+                EventStep::Other
+            }
+
+            // 文書型宣言イベント
+            Ok(Event::DocType(e)) => {
+                let content = reader.decoder().decode(&e)?;
+                document_info.set_doctype(XmlDocType::parse(&content));
+                EventStep::Other
+            }
+
+            // その他のイベントは、XMLの取得のみ行う
+            _ => EventStep::Other,
+        };
+
+        if raw_xml_capture.is_enabled() {
+            let consumed = reader.buffer_position() - position;
+            let raw = raw_event_bytes(&buf, step != EventStep::Text, consumed);
+            let current_tag_hierarchy = state.get_tag_hierarchy();
+            match step {
+                EventStep::Start => raw_xml_capture.start(current_tag_hierarchy, &raw),
+                EventStep::End => {
+                    let captured = raw_xml_capture.end(current_tag_hierarchy, &raw);
+                    put_raw_xml(
+                        reader,
+                        current_tag_hierarchy,
+                        state.get_config(),
+                        &mut tag_store,
+                        captured,
+                    )?;
+                }
+                EventStep::Empty => {
+                    raw_xml_capture.start(current_tag_hierarchy, &raw);
+                    let captured = raw_xml_capture.end(current_tag_hierarchy, &[]);
+                    put_raw_xml(
+                        reader,
+                        current_tag_hierarchy,
+                        state.get_config(),
+                        &mut tag_store,
+                        captured,
+                    )?;
+                }
+                EventStep::Text | EventStep::Other => raw_xml_capture.push(&raw),
+            }
+        }
+
+        if matches!(step, EventStep::End | EventStep::Empty) {
+            let value = tag_store.remove(state.get_tag_hierarchy());
+            state.end_tag(
+                step == EventStep::Empty,
+                value,
+                &mut out_put_value,
+                &mut on_group,
+            )?;
         }
         buf.clear(); // メモリ節約のためbufをクリアする
     }
-    Ok(out_put_values)
+    Ok(document_info)
+}
+
+/// コメント・処理命令のうち設定で指定されたものを保持します。
+///
+/// タグの外(文書レベル)にあるものは`document_info`に、タグの直下にあるものはそのタグの値に追加します。
+fn put_comment_or_processing_instruction(
+    current_tag_hierarchy: &Vec<String>,
+    read_config: &XmlReadConfig,
+    config: Option<&XmlTagReadConfig>,
+    tag_store: &mut TagHierarchyStore,
+    document_info: &mut XmlDocumentInfo,
+    tag_value: TagValue,
+) -> Result<(), Box<dyn Error>> {
+    if current_tag_hierarchy.is_empty() {
+        if read_config.is_document_tag_value(&tag_value) {
+            document_info.push_document_value(tag_value);
+        }
+        return Ok(());
+    }
+    if !config.is_some_and(|config| config.is_target_tag_value(&tag_value)) {
+        return Ok(());
+    }
+    if let Some(out_put_tag_value) = tag_store.get_mut(current_tag_hierarchy) {
+        out_put_tag_value.put_comment_or_processing_instruction(
+            current_tag_hierarchy,
+            tag_value.get_value_type().clone(),
+            tag_value.get_tag_name().to_string(),
+            tag_value.get_value().to_string(),
+        )?;
+    }
+    Ok(())
+}
+
+/// 読み込んだイベントの種類です。(イベントのバッファを解放した後の処理に使用する)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventStep {
+    Start,
+    End,
+    Empty,
+    Text,
+    Other,
+}
+
+/// 取得したタグのXMLを、設定で指定された種類(`inner_xml`・`outer_xml`)の値として保持します。
+fn put_raw_xml<R>(
+    reader: &quick_xml::Reader<R>,
+    current_tag_hierarchy: &Vec<String>,
+    config: Option<&XmlTagReadConfig>,
+    tag_store: &mut TagHierarchyStore,
+    captured: Option<(Vec<u8>, Vec<u8>)>,
+) -> Result<(), Box<dyn Error>> {
+    let (Some((inner, outer)), Some(config)) = (captured, config) else {
+        return Ok(());
+    };
+    let Some(out_put_tag_value) = tag_store.get_mut(current_tag_hierarchy) else {
+        return Ok(());
+    };
+    let decoder = reader.decoder();
+    for (tag_value_name, value_type, raw) in [
+        (TagValueName::new_inner_xml(), TagValueType::InnerXml, inner),
+        (TagValueName::new_outer_xml(), TagValueType::OuterXml, outer),
+    ] {
+        if config.contains_target_tag_value_name(&tag_value_name) {
+            out_put_tag_value.put_raw_xml_value(
+                current_tag_hierarchy,
+                value_type,
+                decoder.decode(&raw)?.into_owned(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// 開始タグ・空要素タグを開き、言語・順番と、属性のうち設定で指定されたものを保持します。
+fn start_tag(
+    state: &mut ReadState<String, String>,
+    tag_store: &mut TagHierarchyStore,
+    start: &BytesStart,
+    is_empty: bool,
+) -> Result<(), Box<dyn Error>> {
+    let elm_name = get_elm_name(&BytesTag::Start(start));
+    let (xml_lang, lang) = get_lang_attributes(start.attributes());
+    let started = state.start_tag(
+        elm_name,
+        is_empty,
+        xml_lang.map(Cow::into_owned),
+        lang.map(Cow::into_owned),
+    );
+    put_start_tag(
+        state.get_tag_hierarchy(),
+        state.get_config(),
+        tag_store,
+        start,
+        started,
+    )
+}
+
+/// 開始タグの言語・順番と、属性のうち設定で指定されたものを保持します。(`config`がない場合は何もしない)
+fn put_start_tag(
+    current_tag_hierarchy: &Vec<String>,
+    config: Option<&XmlTagReadConfig>,
+    tag_store: &mut TagHierarchyStore,
+    start: &BytesStart,
+    started: StartedTag<String>,
+) -> Result<(), Box<dyn Error>> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    if let Some(out_put_tag_value) = tag_store.get_mut(current_tag_hierarchy) {
+        out_put_tag_value.set_lang(started.lang);
+        out_put_tag_value.set_document_order(started.document_order);
+        out_put_tag_value.set_sibling_index(started.sibling_index);
+        out_put_tag_value.put_selected_tag_attribute_from_start_tag(
+            current_tag_hierarchy,
+            config,
+            start,
+        )?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -123,8 +370,8 @@ mod tests {
     use log::{error, info};
 
     use crate::reader::{
-        tag_value::TagValue, tag_value_type::TagValueType, util::string_vec,
-        xml_read_config::XmlTagReadConfig,
+        pick_up::build_read_config, tag_value::TagValue, tag_value_type::TagValueType,
+        util::string_vec, xml_read_config::XmlTagReadConfig,
     };
 
     use super::*;
@@ -517,4 +764,217 @@ mod tests {
             }
         }
     }
+
+    #[test_log::test]
+    fn test_read_xml_empty_element() {
+        let programme = string_vec(vec![TV_TAG, PROGRAMME_TAG]);
+        let icon = string_vec(vec![TV_TAG, PROGRAMME_TAG, "icon"]);
+        let mut read_config = XmlReadConfig::new(programme.clone());
+        for (tag_hierarchy, attr) in [(&programme, PROGRAMME_START_TIME_ATTR), (&icon, "src")] {
+            read_config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy.clone(),
+                    std::collections::HashSet::from_iter(vec![TagValueName::new_attribute(
+                        attr.to_string(),
+                    )]),
+                ))
+                .unwrap();
+        }
+        let mut reader = quick_xml::Reader::from_str(
+            r#"<tv>
+                <programme start="1"><icon src="a.png"/><icon src="b.png"/></programme>
+                <programme start="2"/>
+            </tv>"#,
+        );
+        let out_put_values = read_xml(&mut reader, &read_config).unwrap();
+        assert_eq!(out_put_values.len(), 2);
+        let icons: Vec<String> = out_put_values[0]
+            .iter()
+            .filter(|x| x.get_tag_hierarchy() == icon)
+            .map(|x| x.get_tag_values()[0].get_value().to_string())
+            .collect();
+        assert_eq!(icons, vec!["a.png", "b.png"]);
+        assert_eq!(out_put_values[1].len(), 1);
+        assert_eq!(out_put_values[1][0].get_tag_values()[0].get_value(), "2");
+    }
+
+    #[test_log::test]
+    fn test_read_xml_raw_xml() {
+        let programme = string_vec(vec![TV_TAG, PROGRAMME_TAG]);
+        let desc = string_vec(vec![TV_TAG, PROGRAMME_TAG, DESCRIPTION_TAG]);
+        let icon = string_vec(vec![TV_TAG, PROGRAMME_TAG, "icon"]);
+        let mut read_config = XmlReadConfig::new(programme.clone());
+        for (tag_hierarchy, tag_value_names) in [
+            (&programme, vec![TagValueName::new_inner_xml()]),
+            (
+                &desc,
+                vec![
+                    TagValueName::new_inner_xml(),
+                    TagValueName::new_outer_xml(),
+                    TagValueName::new_tag_text(),
+                ],
+            ),
+            (&icon, vec![TagValueName::new_outer_xml()]),
+        ] {
+            read_config
+                .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                    tag_hierarchy.clone(),
+                    std::collections::HashSet::from_iter(tag_value_names),
+                ))
+                .unwrap();
+        }
+        let desc_xml =
+            "<desc lang='ja'>A &amp; <b >B</b  ><br/><!-- c --><![CDATA[<x>]]>\n</desc\n>";
+        let programme_inner = format!("\n  {}<icon src=\"a.png\" />\n", desc_xml);
+        let xml = format!("<tv><programme>{}</programme></tv>", programme_inner);
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        let out_put_values = read_xml(&mut reader, &read_config).unwrap();
+        let value_of = |tag_hierarchy: &Vec<String>, tag_value_name: TagValueName| {
+            out_put_values[0]
+                .iter()
+                .find(|x| x.get_tag_hierarchy() == *tag_hierarchy)
+                .and_then(|x| x.get_value(&tag_value_name))
+                .map(|x| x.to_string())
+        };
+        assert_eq!(
+            value_of(&desc, TagValueName::new_outer_xml()).as_deref(),
+            Some(desc_xml)
+        );
+        assert_eq!(
+            value_of(&desc, TagValueName::new_inner_xml()).as_deref(),
+            Some("A &amp; <b >B</b  ><br/><!-- c --><![CDATA[<x>]]>\n")
+        );
+        assert_eq!(
+            value_of(&desc, TagValueName::new_tag_text()).as_deref(),
+            Some("A & ")
+        );
+        assert_eq!(
+            value_of(&icon, TagValueName::new_outer_xml()).as_deref(),
+            Some("<icon src=\"a.png\" />")
+        );
+        assert_eq!(value_of(&icon, TagValueName::new_inner_xml()), None);
+        assert_eq!(
+            value_of(&programme, TagValueName::new_inner_xml()),
+            Some(programme_inner)
+        );
+    }
+
+    #[test_log::test]
+    fn test_read_xml_document_comments_and_pis() {
+        let programme = string_vec(vec![TV_TAG, PROGRAMME_TAG]);
+        let mut read_config = XmlReadConfig::new(programme.clone());
+        read_config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                programme.clone(),
+                std::collections::HashSet::from_iter(vec![
+                    TagValueName::new_comment(),
+                    TagValueName::new_processing_instruction("*".to_string()),
+                ]),
+            ))
+            .unwrap();
+        read_config
+            .set_document_tag_value_names(std::collections::HashSet::from_iter(vec![
+                TagValueName::new_comment(),
+                TagValueName::new_processing_instruction("xml-stylesheet".to_string()),
+            ]))
+            .unwrap();
+        let mut reader = quick_xml::Reader::from_str(
+            r#"<?xml version="1.0"?>
+            <!-- generator: epgdump 1.2 -->
+            <?xml-stylesheet href="tv.xsl" type="text/xsl"?>
+            <?other ignored?>
+            <tv><!-- not in a configured tag -->
+              <programme start="1"><!-- note --><?rating pg?><title>T<!-- in title --></title></programme>
+            </tv>"#,
+        );
+        let document = read_xml_document(&mut reader, &read_config).unwrap();
+        let info = document.get_info();
+        assert_eq!(info.get_comments(), vec![" generator: epgdump 1.2 "]);
+        assert_eq!(
+            info.get_processing_instructions("xml-stylesheet"),
+            vec![r#"href="tv.xsl" type="text/xsl""#]
+        );
+        assert!(info.get_processing_instructions("other").is_empty());
+        assert_eq!(info.get_version(), Some("1.0"));
+        assert_eq!(info.get_encoding(), None);
+        assert_eq!(info.get_doctype(), None);
+
+        let values = document.get_groups()[0][0].get_tag_values();
+        assert_eq!(
+            values,
+            vec![
+                TagValue::new(
+                    TagValueType::Comment,
+                    PROGRAMME_TAG.to_string(),
+                    " note ".to_string()
+                ),
+                TagValue::new(
+                    TagValueType::ProcessingInstruction,
+                    "rating".to_string(),
+                    "pg".to_string()
+                ),
+            ]
+        );
+
+        assert!(
+            read_config
+                .set_document_tag_value_names(std::collections::HashSet::from_iter(vec![
+                    TagValueName::new_tag_text()
+                ]))
+                .is_err()
+        );
+    }
+
+    #[test_log::test]
+    fn test_read_xml_sibling_and_group_index() {
+        let read_config = build_read_config(
+            "tv/programme",
+            vec![
+                ("extdesc/item_description", TagValueName::new_tag_text()),
+                ("extdesc/item", TagValueName::new_tag_text()),
+            ],
+        );
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let out_put_values = read_xml(&mut reader, &read_config).unwrap();
+        assert_eq!(out_put_values.len(), 2);
+        for (group_index, inner_values) in out_put_values.iter().enumerate() {
+            assert!(
+                inner_values
+                    .iter()
+                    .all(|x| x.get_group_index() == group_index)
+            );
+        }
+        let pairs: Vec<(usize, &str)> = out_put_values[0]
+            .iter()
+            .filter(|x| x.get_tag_name().unwrap() == "item")
+            .map(|x| (x.get_sibling_index(), x.get_text().unwrap()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (0, "EXT_DESC_ITEM_1"),
+                (1, "EXT_DESC_ITEM_2"),
+                (2, "EXT_DESC_ITEM_2")
+            ]
+        );
+        let description = out_put_values[0]
+            .iter()
+            .find(|x| x.get_tag_name().unwrap() == "item_description" && x.get_sibling_index() == 1)
+            .unwrap();
+        assert_eq!(description.get_text(), Some("EXT_DESC_ITEM_NAME_2"));
+    }
+
+    #[test_log::test]
+    fn test_read_xml_document_declaration_and_doctype() {
+        let read_config = XmlReadConfig::new(string_vec(vec![TV_TAG, PROGRAMME_TAG]));
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let document = read_xml_document(&mut reader, &read_config).unwrap();
+        let info = document.get_info();
+        assert_eq!(info.get_version(), Some("1.0"));
+        assert_eq!(info.get_encoding(), Some("UTF-8"));
+        assert_eq!(info.get_standalone(), None);
+        let doctype = info.get_doctype().unwrap();
+        assert_eq!(doctype.get_name(), "tv");
+        assert_eq!(doctype.get_system_id(), Some("xmltv.dtd"));
+    }
 }
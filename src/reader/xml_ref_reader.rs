@@ -0,0 +1,408 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+
+use log::warn;
+use quick_xml::events::Event;
+use quick_xml::events::attributes::Attributes;
+
+use super::{
+    output_tag_value_ref::OutPutTagValueRef,
+    read_state::{ReadState, get_lang_attributes},
+    tag_matcher::NodeId,
+    tag_value_ref::TagValueRef,
+    tag_value_type::TagValueType,
+    xml_read_config::{TagValueName, XmlReadConfig, XmlTagReadConfig},
+};
+
+/// メモリ上のXMLを読み込み、設定に従って取得した値を借用したまま、グループごとにまとめて返します。
+///
+/// 結果は`read_xml`と同じ内容で(`OutPutTagValueRef::into_owned`で`OutPutTagValue`になる)、
+/// 名前と値はXMLから、タグ階層は読み取り設定から借用します。
+/// 値の絞り込みや件数の集計のみであれば、値ごとの`String`の確保は行いません。
+/// (文書レベルのコメント・処理命令、XML宣言は取得しません。`read_xml_document`を使用してください)
+///
+/// # 例
+/// ```ignore
+/// let xml = std::fs::read("epg.xml")?;
+/// let groups = read_xml_ref(&xml, &read_config)?;
+/// let news = groups.iter().flatten().filter(|x| x.get_text() == Some("NEWS")).count();
+/// ```
+pub fn read_xml_ref<'a, X>(
+    xml: &'a X,
+    read_config: &'a XmlReadConfig,
+) -> Result<Vec<Vec<OutPutTagValueRef<'a>>>, Box<dyn Error>>
+where
+    X: AsRef<[u8]> + ?Sized,
+{
+    let mut out_put_values: Vec<Vec<OutPutTagValueRef<'a>>> = Vec::new();
+    read_xml_ref_each(xml, read_config, |out_put_value| {
+        out_put_values.push(out_put_value);
+        Ok(())
+    })?;
+    Ok(out_put_values)
+}
+
+/// メモリ上のXMLを読み込み、グループが1つ完成するたびに`on_group`を呼び出します。(`read_xml_ref`を参照)
+///
+/// `on_group`がエラーを返した場合は、読み込みを中止してそのエラーを返します。
+pub fn read_xml_ref_each<'a, X, F>(
+    xml: &'a X,
+    read_config: &'a XmlReadConfig,
+    mut on_group: F,
+) -> Result<(), Box<dyn Error>>
+where
+    X: AsRef<[u8]> + ?Sized,
+    F: FnMut(Vec<OutPutTagValueRef<'a>>) -> Result<(), Box<dyn Error>>,
+{
+    let xml: &'a [u8] = xml.as_ref();
+    let mut reader = quick_xml::Reader::from_reader(xml);
+
+    // 開いているタグのタグ階層・言語・順番など(タグ名と言語はXMLから借用する)
+    let mut state: ReadState<&'a str, Cow<'a, str>> = ReadState::new(read_config);
+    // 読み取り設定のあるタグの値(タグ階層の代わりにノードをキーにする)
+    let mut tag_store: HashMap<NodeId, OutPutTagValueRef<'a>> = HashMap::new();
+    // `inner_xml`・`outer_xml`を取得するタグの、開始タグの先頭と末尾の位置
+    let mut raw_xml_starts: Vec<(NodeId, usize, usize)> = Vec::new();
+    let mut out_put_value: Vec<OutPutTagValueRef<'a>> = Vec::new();
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader.read_event()?;
+        let end_position = reader.buffer_position() as usize;
+        let step = match event {
+            Event::Eof => break,
+
+            // 開始イベント・空要素イベント(空要素は、開始と終了が続けて来たものとして扱う)
+            Event::Start(_) | Event::Empty(_) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                let content = get_tag_content(xml, position, end_position, is_empty)?;
+                let name_len = content
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .unwrap_or(content.len());
+                let elm_name = &content[..name_len];
+                let (xml_lang, lang) = get_lang_attributes(Attributes::new(content, name_len));
+                let started = state.start_tag(elm_name, is_empty, xml_lang, lang);
+                let node = state.current_node();
+                if let (Some(node), Some(config)) = (node, state.get_config()) {
+                    let out_put_tag_value = tag_store
+                        .entry(node)
+                        .or_insert_with(|| OutPutTagValueRef::new(config.get_tag_hierarchy_ref()));
+                    out_put_tag_value.set_start_tag(
+                        started.lang,
+                        started.document_order,
+                        started.sibling_index,
+                    );
+                    put_attributes(out_put_tag_value, config, content, name_len);
+                    if config.has_raw_xml_target() {
+                        raw_xml_starts.push((node, position, end_position));
+                    }
+                }
+                if is_empty { Step::Empty } else { Step::Other }
+            }
+
+            // 終了イベント
+            Event::End(end) => {
+                state.check_end_tag(end.name().as_ref())?;
+                Step::End
+            }
+
+            // テキストイベント
+            Event::Text(e) => {
+                let out_put_tag_value = state
+                    .current_node()
+                    .filter(|_| state.is_text_target())
+                    .and_then(|node| tag_store.get_mut(&node));
+                if let Some(out_put_tag_value) = out_put_tag_value {
+                    let tag_name = out_put_tag_value.get_tag_name();
+                    out_put_tag_value.put_tag_value(TagValueRef::new(
+                        TagValueType::TagText,
+                        Cow::Borrowed(tag_name),
+                        e.unescape()?,
+                    ));
+                }
+                Step::Other
+            }
+
+            // コメントイベント(タグの直下にあるもののみ)
+            Event::Comment(e) => {
+                let comment = cow_to_str(e.into_inner())?;
+                let node = state.current_node();
+                if let Some(out_put_tag_value) = node.and_then(|node| tag_store.get_mut(&node)) {
+                    let tag_name = out_put_tag_value.get_tag_name();
+                    put_comment_or_processing_instruction(
+                        state.get_config(),
+                        out_put_tag_value,
+                        TagValueRef::new(TagValueType::Comment, Cow::Borrowed(tag_name), comment),
+                    );
+                }
+                Step::Other
+            }
+
+            // 処理命令イベント(タグの直下にあるもののみ)
+            Event::PI(e) => {
+                let (target, content) = split_processing_instruction(cow_to_str(e.into_inner())?);
+                let node = state.current_node();
+                if let Some(out_put_tag_value) = node.and_then(|node| tag_store.get_mut(&node)) {
+                    put_comment_or_processing_instruction(
+                        state.get_config(),
+                        out_put_tag_value,
+                        TagValueRef::new(TagValueType::ProcessingInstruction, target, content),
+                    );
+                }
+                Step::Other
+            }
+
+            // その他のイベントは読み飛ばす
+            _ => Step::Other,
+        };
+        if step == Step::Other {
+            continue;
+        }
+
+        // タグの終了
+        let node = state.current_node();
+        let mut value = node.and_then(|node| tag_store.remove(&node));
+        if let Some(node) = node
+            && raw_xml_starts.last().is_some_and(|(x, _, _)| *x == node)
+        {
+            let (_, start, start_end) = raw_xml_starts.pop().unwrap_or_default();
+            let (inner, outer) = match step {
+                Step::Empty => (&xml[start_end..start_end], &xml[start..start_end]),
+                _ => (&xml[start_end..position], &xml[start..end_position]),
+            };
+            if let (Some(config), Some(value)) = (state.get_config(), value.as_mut()) {
+                put_raw_xml(value, config, inner, outer)?;
+            }
+        }
+        state.end_tag(
+            step == Step::Empty,
+            value,
+            &mut out_put_value,
+            &mut on_group,
+        )?;
+    }
+    Ok(())
+}
+
+/// 読み込んだイベントの種類です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    End,
+    Empty,
+    Other,
+}
+
+/// 開始タグ・空要素タグの、`<`・`/>`・`>`を除いた部分を取得します。
+fn get_tag_content(
+    xml: &[u8],
+    start: usize,
+    end: usize,
+    is_empty: bool,
+) -> Result<&str, Box<dyn Error>> {
+    let suffix_len = if is_empty { 2 } else { 1 };
+    let raw = xml
+        .get(start..end)
+        .filter(|raw| raw.len() > suffix_len && raw[0] == b'<' && raw[raw.len() - 1] == b'>')
+        .ok_or_else(|| format!("unexpected tag position: {}..{}", start, end))?;
+    Ok(std::str::from_utf8(&raw[1..raw.len() - suffix_len])?)
+}
+
+/// 開始タグの属性のうち、設定で指定されたものをタグの値に追加します。
+fn put_attributes<'a>(
+    out_put_tag_value: &mut OutPutTagValueRef<'a>,
+    config: &XmlTagReadConfig,
+    content: &'a str,
+    name_len: usize,
+) {
+    for attr in Attributes::new(content, name_len).flatten() {
+        let Ok(attr_name) = std::str::from_utf8(attr.key.into_inner()) else {
+            warn!(
+                "FAILED TO CONVERT ATTRIBUTE NAME TO STRING: {}",
+                &content[..name_len]
+            );
+            continue;
+        };
+        if !config.is_target_attribute(attr_name) {
+            continue;
+        }
+        let Ok(attr_value) = cow_to_str(attr.value) else {
+            warn!("FAILED TO CONVERT ATTRIBUTE VALUE TO STRING: {}", attr_name);
+            continue;
+        };
+        out_put_tag_value.put_tag_value(TagValueRef::new(
+            TagValueType::AttributeValue,
+            Cow::Borrowed(attr_name),
+            attr_value,
+        ));
+    }
+}
+
+/// タグのXMLを、設定で指定された種類(`inner_xml`・`outer_xml`)の値としてタグの値に追加します。
+fn put_raw_xml<'a>(
+    out_put_tag_value: &mut OutPutTagValueRef<'a>,
+    config: &XmlTagReadConfig,
+    inner: &'a [u8],
+    outer: &'a [u8],
+) -> Result<(), Box<dyn Error>> {
+    let tag_name = out_put_tag_value.get_tag_name();
+    for (tag_value_name, value_type, raw) in [
+        (TagValueName::new_inner_xml(), TagValueType::InnerXml, inner),
+        (TagValueName::new_outer_xml(), TagValueType::OuterXml, outer),
+    ] {
+        if config.contains_target_tag_value_name(&tag_value_name) {
+            out_put_tag_value.put_tag_value(TagValueRef::new(
+                value_type,
+                Cow::Borrowed(tag_name),
+                Cow::Borrowed(std::str::from_utf8(raw)?),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// コメント・処理命令が設定で指定されたものであれば、タグの値に追加します。
+fn put_comment_or_processing_instruction<'a>(
+    config: Option<&XmlTagReadConfig>,
+    out_put_tag_value: &mut OutPutTagValueRef<'a>,
+    tag_value: TagValueRef<'a>,
+) {
+    if config.is_some_and(|config| {
+        config.is_target_type_and_name(tag_value.get_value_type(), tag_value.get_tag_name())
+    }) {
+        out_put_tag_value.push_tag_value(tag_value);
+    }
+}
+
+/// 処理命令をターゲット名と内容(前後の空白を除く)に分けます。
+fn split_processing_instruction(pi: Cow<'_, str>) -> (Cow<'_, str>, Cow<'_, str>) {
+    let target_len = |pi: &str| {
+        pi.find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(pi.len())
+    };
+    match pi {
+        Cow::Borrowed(pi) => {
+            let (target, content) = pi.split_at(target_len(pi));
+            (Cow::Borrowed(target), Cow::Borrowed(content.trim()))
+        }
+        Cow::Owned(pi) => {
+            let (target, content) = pi.split_at(target_len(&pi));
+            (
+                Cow::Owned(target.to_string()),
+                Cow::Owned(content.trim().to_string()),
+            )
+        }
+    }
+}
+
+/// バイト列を文字列に変換します。(借用している場合は借用したまま)
+fn cow_to_str(bytes: Cow<'_, [u8]>) -> Result<Cow<'_, str>, std::str::Utf8Error> {
+    match bytes {
+        Cow::Borrowed(bytes) => std::str::from_utf8(bytes).map(Cow::Borrowed),
+        Cow::Owned(bytes) => String::from_utf8(bytes)
+            .map(Cow::Owned)
+            .map_err(|e| e.utf8_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::pick_up::build_read_config;
+    use crate::reader::xml_reader::read_xml;
+
+    #[test_log::test]
+    fn test_read_xml_ref_matches_read_xml() {
+        let text = TagValueName::new_tag_text;
+        let xml = std::fs::read("test_xml/sample1.xml").unwrap();
+        let configs = vec![
+            build_read_config(
+                "tv/programme",
+                vec![
+                    ("", TagValueName::new_attribute("start".to_string())),
+                    ("title", text()),
+                    ("category", text()),
+                    ("category", TagValueName::new_attribute("lang".to_string())),
+                    ("video/aspect", text()),
+                    ("extdesc/item", text()),
+                    ("extdesc", TagValueName::new_inner_xml()),
+                    ("extdesc", TagValueName::new_outer_xml()),
+                ],
+            ),
+            build_read_config("tv/channel", vec![("", TagValueName::new_all_attributes())]),
+        ];
+        for config in &configs {
+            let mut reader = quick_xml::Reader::from_reader(xml.as_slice());
+            let expected = read_xml(&mut reader, config).unwrap();
+            let groups = read_xml_ref(&xml, config).unwrap();
+            // 実体参照を含まない値は、すべて借用している
+            assert!(
+                groups
+                    .iter()
+                    .flatten()
+                    .flat_map(|x| x.get_tag_values())
+                    .all(TagValueRef::is_borrowed)
+            );
+            let actual: Vec<Vec<_>> = groups
+                .into_iter()
+                .map(|group| {
+                    group
+                        .into_iter()
+                        .map(OutPutTagValueRef::into_owned)
+                        .collect()
+                })
+                .collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test_log::test]
+    fn test_read_xml_ref_escaped_values_and_comments() {
+        let config = build_read_config(
+            "tv/programme",
+            vec![
+                ("title", TagValueName::new_tag_text()),
+                ("", TagValueName::new_comment()),
+                (
+                    "",
+                    TagValueName::new_processing_instruction("*".to_string()),
+                ),
+            ],
+        );
+        let xml = r#"<tv><programme xml:lang="ja"><!-- note --><?rating pg ?><title>A &amp; B</title><title>C</title></programme></tv>"#;
+        let groups = read_xml_ref(xml, &config).unwrap();
+        let mut reader = quick_xml::Reader::from_str(xml);
+        let expected = read_xml(&mut reader, &config).unwrap();
+        assert_eq!(
+            groups[0]
+                .iter()
+                .cloned()
+                .map(OutPutTagValueRef::into_owned)
+                .collect::<Vec<_>>(),
+            expected[0]
+        );
+
+        let titles: Vec<&TagValueRef> = groups[0][..2]
+            .iter()
+            .flat_map(|x| x.get_tag_values())
+            .collect();
+        assert_eq!(titles[0].get_value(), "A & B");
+        assert!(!titles[0].is_borrowed());
+        assert_eq!(titles[1].get_value(), "C");
+        assert!(titles[1].is_borrowed());
+        assert_eq!(groups[0][1].get_sibling_index(), 1);
+        assert_eq!(groups[0][0].get_lang(), Some("ja"));
+
+        let programme = &groups[0][2];
+        assert_eq!(
+            programme.get_value(&TagValueName::new_comment()),
+            Some(" note ")
+        );
+        assert_eq!(
+            programme.get_value(&TagValueName::new_processing_instruction(
+                "rating".to_string()
+            )),
+            Some("pg")
+        );
+    }
+}
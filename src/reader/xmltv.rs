@@ -0,0 +1,94 @@
+//! XMLTV形式(`<tv>`、`<channel>`、`<programme>`)の文書を扱うための機能です。
+//!
+//! `channel_read_config`・`programme_read_config`で用意された`XmlReadConfig`を使って`read_xml`で読み込み、
+//! `Channel`・`Programme`に変換します。
+//!
+//! ```no_run
+//! use pick_up_xml_value::reader::xmltv::read_programmes_each;
+//!
+//! let mut reader = quick_xml::Reader::from_file("epg.xml").unwrap();
+//! read_programmes_each(&mut reader, |programme| {
+//!     println!("{} {:?}", programme.get_start(), programme.get_title(&["ja_JP", "en"]));
+//!     Ok(())
+//! })
+//! .unwrap();
+//! ```
+
+pub mod channel;
+pub mod programme;
+pub mod time;
+
+pub use channel::{Channel, channel_read_config, read_channels, read_channels_each};
+pub use programme::{
+    Actor, Audio, Credits, EpisodeNum, Programme, Rating, Video, programme_read_config,
+    read_programmes, read_programmes_each,
+};
+
+pub use super::lang::LangText;
+use super::lang::select_lang_text;
+use super::output_tag_value::OutPutTagValue;
+
+pub(in crate::reader) const TV_TAG: &str = "tv";
+pub(in crate::reader) const CHANNEL_TAG: &str = "channel";
+pub(in crate::reader) const PROGRAMME_TAG: &str = "programme";
+
+/// アイコン(`<icon src="..." width="..." height="..."/>`)です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Icon {
+    /// 画像のURL
+    src: String,
+    /// 幅
+    width: Option<u32>,
+    /// 高さ
+    height: Option<u32>,
+}
+
+impl Icon {
+    /// 画像のURLを取得します。
+    pub fn get_src(&self) -> &str {
+        &self.src
+    }
+
+    /// 幅を取得します。
+    pub fn get_width(&self) -> Option<u32> {
+        self.width
+    }
+
+    /// 高さを取得します。
+    pub fn get_height(&self) -> Option<u32> {
+        self.height
+    }
+}
+
+/// 複数の`LangText`から、優先する言語のテキストを取得します。(規則は`select_lang_text`と同じ)
+pub(in crate::reader) fn select_lang<'a>(
+    lang_texts: &'a [LangText],
+    preferred_langs: &[&str],
+) -> Option<&'a str> {
+    select_lang_text(lang_texts, preferred_langs).map(|x| x.get_value())
+}
+
+/// グループのタグ階層からの相対パスを取得します。(グループのタグ自身は`""`)
+pub(in crate::reader) fn relative_path(
+    out_put_tag_value: &OutPutTagValue,
+    tag_group_hierarchy_len: usize,
+) -> String {
+    let tag_hierarchy = out_put_tag_value.get_tag_hierarchy();
+    tag_hierarchy
+        .get(tag_group_hierarchy_len..)
+        .unwrap_or_default()
+        .join("/")
+}
+
+/// アイコンを取得します。(`src`属性がない場合は`None`)
+pub(in crate::reader) fn icon_of(out_put_tag_value: &OutPutTagValue) -> Option<Icon> {
+    out_put_tag_value.get_attribute("src").map(|src| Icon {
+        src: src.to_string(),
+        width: out_put_tag_value
+            .get_attribute("width")
+            .and_then(|x| x.trim().parse().ok()),
+        height: out_put_tag_value
+            .get_attribute("height")
+            .and_then(|x| x.trim().parse().ok()),
+    })
+}
@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::io::BufRead;
+
+use crate::reader::output_tag_value::OutPutTagValue;
+use crate::reader::pick_up::build_read_config;
+use crate::reader::xml_read_config::{TagValueName, XmlReadConfig};
+use crate::reader::xml_reader::read_xml_each;
+
+use super::{CHANNEL_TAG, Icon, LangText, TV_TAG, icon_of, relative_path, select_lang};
+
+/// XMLTVのチャンネル(`<channel>`)です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Channel {
+    /// チャンネルID(`id`属性)
+    id: String,
+    /// 表示名(`<display-name>`)
+    display_names: Vec<LangText>,
+    /// アイコン(`<icon>`)
+    icons: Vec<Icon>,
+    /// URL(`<url>`)
+    urls: Vec<String>,
+}
+
+impl Channel {
+    /// チャンネルIDを取得します。
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    /// 表示名をすべて取得します。
+    pub fn get_display_names(&self) -> &[LangText] {
+        &self.display_names
+    }
+
+    /// 優先する言語の順に表示名を取得します。(該当する言語がない場合は最初の表示名)
+    ///
+    /// 選択の規則は`select_lang_text`と同じです。
+    pub fn get_display_name(&self, preferred_langs: &[&str]) -> Option<&str> {
+        select_lang(&self.display_names, preferred_langs)
+    }
+
+    /// アイコンを取得します。
+    pub fn get_icons(&self) -> &[Icon] {
+        &self.icons
+    }
+
+    /// URLを取得します。
+    pub fn get_urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    /// `channel_read_config`で読み込んだ1グループ分の`OutPutTagValue`から`Channel`を作成します。
+    pub fn from_group(group: &[OutPutTagValue]) -> Result<Self, Box<dyn Error>> {
+        let mut id = None;
+        let mut display_names = Vec::new();
+        let mut icons = Vec::new();
+        let mut urls = Vec::new();
+        for out_put_tag_value in group {
+            match relative_path(out_put_tag_value, 2).as_str() {
+                "" => id = out_put_tag_value.get_attribute("id").map(String::from),
+                "display-name" => display_names.extend(out_put_tag_value.get_lang_text()),
+                "icon" => icons.extend(icon_of(out_put_tag_value)),
+                "url" => urls.extend(out_put_tag_value.get_text().map(String::from)),
+                _ => (),
+            }
+        }
+        let id = id.ok_or("CHANNEL HAS NO id ATTRIBUTE.")?;
+        Ok(Channel {
+            id,
+            display_names,
+            icons,
+            urls,
+        })
+    }
+}
+
+/// `Channel`に必要な値を読み取る`XmlReadConfig`を作成します。(グループは`tv/channel`)
+pub fn channel_read_config() -> XmlReadConfig {
+    let text = TagValueName::new_tag_text;
+    let attribute = |name: &str| TagValueName::new_attribute(name.to_string());
+    build_read_config(
+        &format!("{}/{}", TV_TAG, CHANNEL_TAG),
+        vec![
+            ("", attribute("id")),
+            ("display-name", text()),
+            ("icon", attribute("src")),
+            ("icon", attribute("width")),
+            ("icon", attribute("height")),
+            ("url", text()),
+        ],
+    )
+}
+
+/// XMLTVの文書を読み込み、`Channel`が1つ完成するたびに`on_channel`を呼び出します。
+pub fn read_channels_each<R, F>(
+    reader: &mut quick_xml::Reader<R>,
+    mut on_channel: F,
+) -> Result<(), Box<dyn Error>>
+where
+    R: BufRead,
+    F: FnMut(Channel) -> Result<(), Box<dyn Error>>,
+{
+    read_xml_each(reader, &channel_read_config(), |group| {
+        on_channel(Channel::from_group(&group)?)
+    })
+}
+
+/// XMLTVの文書を読み込み、すべての`Channel`を返します。
+pub fn read_channels<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+) -> Result<Vec<Channel>, Box<dyn Error>> {
+    let mut channels = Vec::new();
+    read_channels_each(reader, |channel| {
+        channels.push(channel);
+        Ok(())
+    })?;
+    Ok(channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_read_channels() {
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let channels = read_channels(&mut reader).unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].get_id(), "GR2_1032");
+        assert_eq!(
+            channels[0].get_display_names(),
+            &[LangText::new(
+                "CHANNEL_NAME_1_1".to_string(),
+                Some("ja_JP".to_string())
+            )]
+        );
+        assert_eq!(
+            channels[1].get_display_name(&["en"]),
+            Some("CHANNEL_NAME_1_2")
+        );
+        assert!(channels[1].get_icons().is_empty());
+    }
+}
@@ -0,0 +1,602 @@
+use std::error::Error;
+use std::io::BufRead;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::reader::output_tag_value::OutPutTagValue;
+use crate::reader::pick_up::build_read_config;
+use crate::reader::xml_read_config::{TagValueName, XmlReadConfig};
+use crate::reader::xml_reader::read_xml_each;
+
+use super::time::parse_xmltv_time;
+use super::{Icon, LangText, PROGRAMME_TAG, TV_TAG, icon_of, relative_path, select_lang};
+
+/// 出演者(`<actor role="...">`)です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Actor {
+    /// 名前
+    name: String,
+    /// 役名(`role`属性)
+    role: Option<String>,
+}
+
+impl Actor {
+    /// 名前を取得します。
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// 役名を取得します。
+    pub fn get_role(&self) -> Option<&str> {
+        self.role.as_deref()
+    }
+}
+
+/// クレジット(`<credits>`)です。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Credits {
+    directors: Vec<String>,
+    actors: Vec<Actor>,
+    writers: Vec<String>,
+    adapters: Vec<String>,
+    producers: Vec<String>,
+    composers: Vec<String>,
+    editors: Vec<String>,
+    presenters: Vec<String>,
+    commentators: Vec<String>,
+    guests: Vec<String>,
+}
+
+impl Credits {
+    /// 監督(`<director>`)を取得します。
+    pub fn get_directors(&self) -> &[String] {
+        &self.directors
+    }
+
+    /// 出演者(`<actor>`)を取得します。
+    pub fn get_actors(&self) -> &[Actor] {
+        &self.actors
+    }
+
+    /// 脚本(`<writer>`)を取得します。
+    pub fn get_writers(&self) -> &[String] {
+        &self.writers
+    }
+
+    /// 脚色(`<adapter>`)を取得します。
+    pub fn get_adapters(&self) -> &[String] {
+        &self.adapters
+    }
+
+    /// 制作(`<producer>`)を取得します。
+    pub fn get_producers(&self) -> &[String] {
+        &self.producers
+    }
+
+    /// 作曲(`<composer>`)を取得します。
+    pub fn get_composers(&self) -> &[String] {
+        &self.composers
+    }
+
+    /// 編集(`<editor>`)を取得します。
+    pub fn get_editors(&self) -> &[String] {
+        &self.editors
+    }
+
+    /// 司会(`<presenter>`)を取得します。
+    pub fn get_presenters(&self) -> &[String] {
+        &self.presenters
+    }
+
+    /// 解説(`<commentator>`)を取得します。
+    pub fn get_commentators(&self) -> &[String] {
+        &self.commentators
+    }
+
+    /// ゲスト(`<guest>`)を取得します。
+    pub fn get_guests(&self) -> &[String] {
+        &self.guests
+    }
+
+    /// クレジットが1つもないかどうかを確認します。
+    pub fn is_empty(&self) -> bool {
+        *self == Credits::default()
+    }
+}
+
+/// エピソード番号(`<episode-num system="...">`)です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EpisodeNum {
+    /// 番号
+    value: String,
+    /// 番号の体系(`system`属性。例: `xmltv_ns`、`onscreen`)
+    system: Option<String>,
+}
+
+impl EpisodeNum {
+    /// 番号を取得します。
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// 番号の体系を取得します。
+    pub fn get_system(&self) -> Option<&str> {
+        self.system.as_deref()
+    }
+}
+
+/// 映像の情報(`<video>`)です。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Video {
+    present: Option<String>,
+    colour: Option<String>,
+    aspect: Option<String>,
+    quality: Option<String>,
+}
+
+impl Video {
+    /// `<present>`を取得します。
+    pub fn get_present(&self) -> Option<&str> {
+        self.present.as_deref()
+    }
+
+    /// `<colour>`を取得します。
+    pub fn get_colour(&self) -> Option<&str> {
+        self.colour.as_deref()
+    }
+
+    /// `<aspect>`を取得します。(例: `16:9`)
+    pub fn get_aspect(&self) -> Option<&str> {
+        self.aspect.as_deref()
+    }
+
+    /// `<quality>`を取得します。(例: `HDTV`)
+    pub fn get_quality(&self) -> Option<&str> {
+        self.quality.as_deref()
+    }
+}
+
+/// 音声の情報(`<audio>`)です。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Audio {
+    present: Option<String>,
+    stereo: Option<String>,
+}
+
+impl Audio {
+    /// `<present>`を取得します。
+    pub fn get_present(&self) -> Option<&str> {
+        self.present.as_deref()
+    }
+
+    /// `<stereo>`を取得します。(例: `stereo`、`surround`)
+    pub fn get_stereo(&self) -> Option<&str> {
+        self.stereo.as_deref()
+    }
+}
+
+/// レーティング(`<rating>`、`<star-rating>`)です。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rating {
+    /// 値(`<value>`)
+    value: Option<String>,
+    /// 体系(`system`属性)
+    system: Option<String>,
+    /// アイコン(`<icon>`)
+    icons: Vec<Icon>,
+}
+
+impl Rating {
+    /// 値を取得します。
+    pub fn get_value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// 体系を取得します。
+    pub fn get_system(&self) -> Option<&str> {
+        self.system.as_deref()
+    }
+
+    /// アイコンを取得します。
+    pub fn get_icons(&self) -> &[Icon] {
+        &self.icons
+    }
+}
+
+/// XMLTVの番組(`<programme>`)です。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Programme {
+    start: DateTime<FixedOffset>,
+    stop: Option<DateTime<FixedOffset>>,
+    channel: String,
+    titles: Vec<LangText>,
+    sub_titles: Vec<LangText>,
+    descs: Vec<LangText>,
+    credits: Credits,
+    categories: Vec<LangText>,
+    episode_nums: Vec<EpisodeNum>,
+    video: Option<Video>,
+    audio: Option<Audio>,
+    ratings: Vec<Rating>,
+    star_ratings: Vec<Rating>,
+    icons: Vec<Icon>,
+    extdesc: Vec<(String, String)>,
+}
+
+impl Programme {
+    /// 開始日時(`start`属性)を取得します。
+    pub fn get_start(&self) -> &DateTime<FixedOffset> {
+        &self.start
+    }
+
+    /// 終了日時(`stop`属性)を取得します。
+    pub fn get_stop(&self) -> Option<&DateTime<FixedOffset>> {
+        self.stop.as_ref()
+    }
+
+    /// チャンネルID(`channel`属性)を取得します。
+    pub fn get_channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// タイトル(`<title>`)をすべて取得します。
+    pub fn get_titles(&self) -> &[LangText] {
+        &self.titles
+    }
+
+    /// 優先する言語の順にタイトルを取得します。(該当する言語がない場合は最初のタイトル)
+    ///
+    /// 選択の規則は`select_lang_text`と同じです。
+    pub fn get_title(&self, preferred_langs: &[&str]) -> Option<&str> {
+        select_lang(&self.titles, preferred_langs)
+    }
+
+    /// サブタイトル(`<sub-title>`)をすべて取得します。
+    pub fn get_sub_titles(&self) -> &[LangText] {
+        &self.sub_titles
+    }
+
+    /// 説明(`<desc>`)をすべて取得します。
+    pub fn get_descs(&self) -> &[LangText] {
+        &self.descs
+    }
+
+    /// 優先する言語の順に説明を取得します。(該当する言語がない場合は最初の説明)
+    ///
+    /// 選択の規則は`select_lang_text`と同じです。
+    pub fn get_desc(&self, preferred_langs: &[&str]) -> Option<&str> {
+        select_lang(&self.descs, preferred_langs)
+    }
+
+    /// クレジット(`<credits>`)を取得します。
+    pub fn get_credits(&self) -> &Credits {
+        &self.credits
+    }
+
+    /// ジャンル(`<category>`)を取得します。
+    pub fn get_categories(&self) -> &[LangText] {
+        &self.categories
+    }
+
+    /// エピソード番号(`<episode-num>`)を取得します。
+    pub fn get_episode_nums(&self) -> &[EpisodeNum] {
+        &self.episode_nums
+    }
+
+    /// 映像の情報(`<video>`)を取得します。
+    pub fn get_video(&self) -> Option<&Video> {
+        self.video.as_ref()
+    }
+
+    /// 音声の情報(`<audio>`)を取得します。
+    pub fn get_audio(&self) -> Option<&Audio> {
+        self.audio.as_ref()
+    }
+
+    /// レーティング(`<rating>`)を取得します。
+    pub fn get_ratings(&self) -> &[Rating] {
+        &self.ratings
+    }
+
+    /// 評価(`<star-rating>`)を取得します。
+    pub fn get_star_ratings(&self) -> &[Rating] {
+        &self.star_ratings
+    }
+
+    /// アイコン(`<icon>`)を取得します。
+    pub fn get_icons(&self) -> &[Icon] {
+        &self.icons
+    }
+
+    /// 番組詳細(`<extdesc>`の`<item_description>`と`<item>`の組)を出現順に取得します。
+    pub fn get_extdesc(&self) -> &[(String, String)] {
+        &self.extdesc
+    }
+
+    /// `programme_read_config`で読み込んだ1グループ分の`OutPutTagValue`から`Programme`を作成します。
+    ///
+    /// `start`・`channel`属性がない場合や、日時を解析できない場合はエラーを返します。
+    pub fn from_group(group: &[OutPutTagValue]) -> Result<Self, Box<dyn Error>> {
+        let mut start = None;
+        let mut stop = None;
+        let mut channel = None;
+        let mut titles = Vec::new();
+        let mut sub_titles = Vec::new();
+        let mut descs = Vec::new();
+        let mut credits = Credits::default();
+        let mut categories = Vec::new();
+        let mut episode_nums = Vec::new();
+        let mut video: Option<Video> = None;
+        let mut audio: Option<Audio> = None;
+        let mut ratings = Vec::new();
+        let mut star_ratings = Vec::new();
+        let mut icons = Vec::new();
+        let mut extdesc = Vec::new();
+        // 子タグは親タグより先に出力されるので、親タグが来るまで保持しておく
+        let mut rating_value = None;
+        let mut rating_icons = Vec::new();
+        let mut item_description: Option<String> = None;
+
+        for out_put_tag_value in group {
+            let path = relative_path(out_put_tag_value, 2);
+            let text = || out_put_tag_value.get_text().map(String::from);
+            match path.as_str() {
+                "" => {
+                    start = out_put_tag_value.get_attribute("start").map(String::from);
+                    stop = out_put_tag_value.get_attribute("stop").map(String::from);
+                    channel = out_put_tag_value.get_attribute("channel").map(String::from);
+                }
+                "title" => titles.extend(out_put_tag_value.get_lang_text()),
+                "sub-title" => sub_titles.extend(out_put_tag_value.get_lang_text()),
+                "desc" => descs.extend(out_put_tag_value.get_lang_text()),
+                "category" => categories.extend(out_put_tag_value.get_lang_text()),
+                "credits/director" => credits.directors.extend(text()),
+                "credits/actor" => credits.actors.extend(text().map(|name| Actor {
+                    name,
+                    role: out_put_tag_value.get_attribute("role").map(String::from),
+                })),
+                "credits/writer" => credits.writers.extend(text()),
+                "credits/adapter" => credits.adapters.extend(text()),
+                "credits/producer" => credits.producers.extend(text()),
+                "credits/composer" => credits.composers.extend(text()),
+                "credits/editor" => credits.editors.extend(text()),
+                "credits/presenter" => credits.presenters.extend(text()),
+                "credits/commentator" => credits.commentators.extend(text()),
+                "credits/guest" => credits.guests.extend(text()),
+                "episode-num" => episode_nums.extend(text().map(|value| EpisodeNum {
+                    value,
+                    system: out_put_tag_value.get_attribute("system").map(String::from),
+                })),
+                "video/present" => video.get_or_insert_default().present = text(),
+                "video/colour" => video.get_or_insert_default().colour = text(),
+                "video/aspect" => video.get_or_insert_default().aspect = text(),
+                "video/quality" => video.get_or_insert_default().quality = text(),
+                "audio/present" => audio.get_or_insert_default().present = text(),
+                "audio/stereo" => audio.get_or_insert_default().stereo = text(),
+                "rating/value" | "star-rating/value" => rating_value = text(),
+                "rating/icon" | "star-rating/icon" => {
+                    rating_icons.extend(icon_of(out_put_tag_value))
+                }
+                "rating" | "star-rating" => {
+                    let rating = Rating {
+                        value: rating_value.take(),
+                        system: out_put_tag_value.get_attribute("system").map(String::from),
+                        icons: std::mem::take(&mut rating_icons),
+                    };
+                    if path == "rating" {
+                        ratings.push(rating);
+                    } else {
+                        star_ratings.push(rating);
+                    }
+                }
+                "icon" => icons.extend(icon_of(out_put_tag_value)),
+                "extdesc/item_description" => item_description = text(),
+                "extdesc/item" => {
+                    if let Some(item) = text() {
+                        extdesc.push((item_description.take().unwrap_or_default(), item));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let channel = channel.ok_or("PROGRAMME HAS NO channel ATTRIBUTE.")?;
+        let start = start
+            .ok_or_else(|| format!("PROGRAMME HAS NO start ATTRIBUTE. channel {:?}", channel))?;
+        let start = parse_xmltv_time(&start)
+            .map_err(|e| format!("INVALID start OF PROGRAMME. channel {:?} : {}", channel, e))?;
+        let stop = stop
+            .map(|stop| parse_xmltv_time(&stop))
+            .transpose()
+            .map_err(|e| format!("INVALID stop OF PROGRAMME. channel {:?} : {}", channel, e))?;
+        Ok(Programme {
+            start,
+            stop,
+            channel,
+            titles,
+            sub_titles,
+            descs,
+            credits,
+            categories,
+            episode_nums,
+            video,
+            audio,
+            ratings,
+            star_ratings,
+            icons,
+            extdesc,
+        })
+    }
+}
+
+/// `Programme`に必要な値を読み取る`XmlReadConfig`を作成します。(グループは`tv/programme`)
+pub fn programme_read_config() -> XmlReadConfig {
+    let text = TagValueName::new_tag_text;
+    let attribute = |name: &str| TagValueName::new_attribute(name.to_string());
+    let mut targets = vec![
+        ("", attribute("start")),
+        ("", attribute("stop")),
+        ("", attribute("channel")),
+        ("episode-num", text()),
+        ("episode-num", attribute("system")),
+        ("credits/actor", attribute("role")),
+        ("video/present", text()),
+        ("video/colour", text()),
+        ("video/aspect", text()),
+        ("video/quality", text()),
+        ("audio/present", text()),
+        ("audio/stereo", text()),
+        ("extdesc/item_description", text()),
+        ("extdesc/item", text()),
+    ];
+    for path in ["title", "sub-title", "desc", "category"] {
+        targets.push((path, text()));
+    }
+    for path in [
+        "credits/director",
+        "credits/actor",
+        "credits/writer",
+        "credits/adapter",
+        "credits/producer",
+        "credits/composer",
+        "credits/editor",
+        "credits/presenter",
+        "credits/commentator",
+        "credits/guest",
+    ] {
+        targets.push((path, text()));
+    }
+    for (rating, value) in [
+        ("rating", "rating/value"),
+        ("star-rating", "star-rating/value"),
+    ] {
+        targets.push((rating, attribute("system")));
+        targets.push((value, text()));
+    }
+    for icon in ["icon", "rating/icon", "star-rating/icon"] {
+        targets.push((icon, attribute("src")));
+        targets.push((icon, attribute("width")));
+        targets.push((icon, attribute("height")));
+    }
+    build_read_config(&format!("{}/{}", TV_TAG, PROGRAMME_TAG), targets)
+}
+
+/// XMLTVの文書を読み込み、`Programme`が1つ完成するたびに`on_programme`を呼び出します。
+///
+/// 番組をすべてメモリに保持しないため、大きな番組表を順次処理する場合に使用します。
+pub fn read_programmes_each<R, F>(
+    reader: &mut quick_xml::Reader<R>,
+    mut on_programme: F,
+) -> Result<(), Box<dyn Error>>
+where
+    R: BufRead,
+    F: FnMut(Programme) -> Result<(), Box<dyn Error>>,
+{
+    read_xml_each(reader, &programme_read_config(), |group| {
+        on_programme(Programme::from_group(&group)?)
+    })
+}
+
+/// XMLTVの文書を読み込み、すべての`Programme`を返します。
+pub fn read_programmes<R: BufRead>(
+    reader: &mut quick_xml::Reader<R>,
+) -> Result<Vec<Programme>, Box<dyn Error>> {
+    let mut programmes = Vec::new();
+    read_programmes_each(reader, |programme| {
+        programmes.push(programme);
+        Ok(())
+    })?;
+    Ok(programmes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_log::test]
+    fn test_read_programmes() {
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let programmes = read_programmes(&mut reader).unwrap();
+        assert_eq!(programmes.len(), 2);
+        let programme = &programmes[0];
+        assert_eq!(programme.get_channel(), "GR2_1032");
+        assert_eq!(
+            programme.get_start().to_rfc3339(),
+            "2024-11-23T09:00:00+09:00"
+        );
+        assert_eq!(
+            programme.get_stop().map(|x| x.to_rfc3339()).as_deref(),
+            Some("2024-11-23T09:20:00+09:00")
+        );
+        assert_eq!(programme.get_title(&["ja_JP"]), Some("TITLE_1_1"));
+        assert_eq!(programme.get_desc(&[]), Some("DESC_1"));
+        assert_eq!(
+            programme.get_categories(),
+            &[
+                LangText::new("CAT_1".to_string(), Some("ja_JP".to_string())),
+                LangText::new("CAT_2".to_string(), Some("en".to_string())),
+            ]
+        );
+        assert_eq!(
+            programme.get_video().and_then(|x| x.get_aspect()),
+            Some("16:9")
+        );
+        assert_eq!(programme.get_extdesc().len(), 3);
+        assert_eq!(
+            programme.get_extdesc()[0],
+            (
+                "EXT_DESC_ITEM_NAME_1".to_string(),
+                "EXT_DESC_ITEM_1".to_string()
+            )
+        );
+        assert!(programme.get_credits().is_empty());
+        assert_eq!(programmes[1].get_title(&[]), Some("TITLE_1_2"));
+    }
+
+    #[test_log::test]
+    fn test_programme_from_group() {
+        let mut reader = quick_xml::Reader::from_str(
+            r#"<tv>
+              <programme start="20241123090000 +0900" channel="C1">
+                <title lang="en">Title</title>
+                <credits>
+                  <director>D</director>
+                  <actor role="Hero">A1</actor>
+                  <actor>A2</actor>
+                </credits>
+                <episode-num system="onscreen">S1E2</episode-num>
+                <icon src="p.png" width="100" height="50"/>
+                <rating system="VCHIP"><value>TV-G</value><icon src="g.png"/></rating>
+                <rating system="MPAA"><value>PG</value></rating>
+                <star-rating><value>3/5</value></star-rating>
+              </programme>
+              <programme channel="C2" start="2024-11-23"/>
+            </tv>"#,
+        );
+        let mut programmes = Vec::new();
+        let err = read_programmes_each(&mut reader, |programme| {
+            programmes.push(programme);
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("\"C2\""), "{}", err);
+
+        let programme = &programmes[0];
+        let credits = programme.get_credits();
+        assert_eq!(credits.get_directors(), &["D".to_string()]);
+        assert_eq!(credits.get_actors()[0].get_role(), Some("Hero"));
+        assert_eq!(credits.get_actors()[1].get_name(), "A2");
+        assert_eq!(
+            programme.get_episode_nums()[0].get_system(),
+            Some("onscreen")
+        );
+        assert_eq!(programme.get_icons()[0].get_width(), Some(100));
+        let ratings = programme.get_ratings();
+        assert_eq!(ratings.len(), 2);
+        assert_eq!(ratings[0].get_value(), Some("TV-G"));
+        assert_eq!(ratings[0].get_icons()[0].get_src(), "g.png");
+        assert_eq!(ratings[1].get_system(), Some("MPAA"));
+        assert!(ratings[1].get_icons().is_empty());
+        assert_eq!(programme.get_star_ratings()[0].get_value(), Some("3/5"));
+    }
+}
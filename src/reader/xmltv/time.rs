@@ -0,0 +1,252 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::reader::output_tag_value::OutPutTagValue;
+use crate::reader::typed_value::ValueConversionError;
+use crate::reader::xml_read_config::TagValueName;
+
+/// XMLTVの日時(`start`、`stop`属性など)を解析します。
+///
+/// 書式は`YYYYMMDDhhmmss +zzzz`です。
+/// - 日時は先頭から`YYYY`、`YYYYMM`、`YYYYMMDD`、`YYYYMMDDhh`、`YYYYMMDDhhmm`まで省略できます。
+///   省略した部分は、月日が1、時分秒が0になります。
+/// - タイムゾーン(`+hhmm`または`-hhmm`)は、日時との間の空白を省略できます。(`20241123090000+0900`)
+/// - タイムゾーンを省略した場合、または`Z`の場合はUTCとします。
+///
+/// # 戻り値
+/// - `Ok(DateTime<FixedOffset>)`: 解析できた場合
+/// - `Err(String)`: 書式が正しくない、または存在しない日時の場合、理由を含む
+pub fn parse_xmltv_time(s: &str) -> Result<DateTime<FixedOffset>, String> {
+    let s = s.trim();
+    // 先頭の数字を日時、それ以降をタイムゾーンとする
+    let date_time_len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (date_time, offset) = s.split_at(date_time_len);
+    let offset = match offset.trim_start() {
+        "" | "Z" => FixedOffset::east_opt(0).unwrap(),
+        offset => parse_offset(offset)?,
+    };
+    let naive = parse_naive(date_time)?;
+    match offset.from_local_datetime(&naive).single() {
+        Some(date_time) => Ok(date_time),
+        None => Err(format!("{:?} is out of range", s)),
+    }
+}
+
+/// XMLTVの日時の書式で文字列にします。(`YYYYMMDDhhmmss +zzzz`)
+pub fn format_xmltv_time(date_time: &DateTime<FixedOffset>) -> String {
+    date_time.format("%Y%m%d%H%M%S %z").to_string()
+}
+
+fn parse_naive(s: &str) -> Result<NaiveDateTime, String> {
+    if !s.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(format!("{:?} contains non-digit characters", s));
+    }
+    if !matches!(s.len(), 4 | 6 | 8 | 10 | 12 | 14) {
+        return Err(format!(
+            "{:?} must be YYYY, YYYYMM, YYYYMMDD, YYYYMMDDhh, YYYYMMDDhhmm or YYYYMMDDhhmmss",
+            s
+        ));
+    }
+    // 省略された部分を補う
+    let field = |start: usize, default: u32| -> u32 {
+        s.get(start..start + 2)
+            .map(|x| x.parse().unwrap())
+            .unwrap_or(default)
+    };
+    let year: i32 = s[0..4].parse().unwrap();
+    let (month, day) = (field(4, 1), field(6, 1));
+    let (hour, minute, second) = (field(8, 0), field(10, 0), field(12, 0));
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(hour, minute, second))
+        .ok_or_else(|| format!("{:?} is not a valid date and time", s))
+}
+
+fn parse_offset(s: &str) -> Result<FixedOffset, String> {
+    let sign = match s.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(format!("time zone {:?} must be +hhmm or -hhmm", s)),
+    };
+    let digits = &s[1..];
+    if digits.len() != 4 || !digits.bytes().all(|x| x.is_ascii_digit()) {
+        return Err(format!("time zone {:?} must be +hhmm or -hhmm", s));
+    }
+    let hours: i32 = digits[0..2].parse().unwrap();
+    let minutes: i32 = digits[2..4].parse().unwrap();
+    if hours > 23 || minutes > 59 {
+        return Err(format!("time zone {:?} is out of range", s));
+    }
+    Ok(FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).unwrap())
+}
+
+/// 日時の範囲です。(開始を含み、終了を含まない)
+///
+/// 開始・終了のどちらかを`None`にすると、その側は制限しません。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    start: Option<DateTime<FixedOffset>>,
+    end: Option<DateTime<FixedOffset>>,
+}
+
+impl TimeRange {
+    /// 新しい`TimeRange`を作成します。
+    ///
+    /// # 引数
+    /// - `start`: 開始(この日時を含む)
+    /// - `end`: 終了(この日時を含まない)
+    pub fn new(start: Option<DateTime<FixedOffset>>, end: Option<DateTime<FixedOffset>>) -> Self {
+        TimeRange { start, end }
+    }
+
+    /// XMLTVの日時の書式の文字列から`TimeRange`を作成します。
+    pub fn from_xmltv_times(start: Option<&str>, end: Option<&str>) -> Result<Self, String> {
+        Ok(TimeRange {
+            start: start.map(parse_xmltv_time).transpose()?,
+            end: end.map(parse_xmltv_time).transpose()?,
+        })
+    }
+
+    /// 開始を取得します。
+    pub fn get_start(&self) -> Option<DateTime<FixedOffset>> {
+        self.start
+    }
+
+    /// 終了を取得します。
+    pub fn get_end(&self) -> Option<DateTime<FixedOffset>> {
+        self.end
+    }
+
+    /// 日時が範囲内かどうかを確認します。(タイムゾーンが異なる場合も同じ時刻として比較します)
+    pub fn contains(&self, date_time: &DateTime<FixedOffset>) -> bool {
+        self.start.is_none_or(|start| start <= *date_time)
+            && self.end.is_none_or(|end| *date_time < end)
+    }
+
+    /// グループの日時が範囲内かどうかを確認します。
+    ///
+    /// # 引数
+    /// - `group`: `read_xml`が返す1グループ分の`OutPutTagValue`
+    /// - `tag_hierarchy`: 日時を取得するタグ階層(例: `["tv", "programme"]`)
+    /// - `tag_value_name`: 日時を取得する項目(例: `@start`)
+    ///
+    /// # 戻り値
+    /// - `Ok(true)`: 日時が存在し、範囲内の場合
+    /// - `Ok(false)`: 日時が存在しない、または範囲外の場合
+    /// - `Err(ValueConversionError)`: 日時を解析できない場合、タグ階層と値を含む
+    pub fn contains_group(
+        &self,
+        group: &[OutPutTagValue],
+        tag_hierarchy: &[String],
+        tag_value_name: &TagValueName,
+    ) -> Result<bool, ValueConversionError> {
+        for out_put_tag_value in group {
+            if out_put_tag_value.get_tag_hierarchy() != tag_hierarchy {
+                continue;
+            }
+            for tag_value in out_put_tag_value.get_tag_values() {
+                if !tag_value_name.is_match(&tag_value) {
+                    continue;
+                }
+                let date_time = tag_value
+                    .get_xmltv_time()
+                    .map_err(|e| e.with_tag_hierarchy(tag_hierarchy))?;
+                return Ok(self.contains(&date_time));
+            }
+        }
+        Ok(false)
+    }
+
+    /// 日時が範囲内のグループだけを取り出します。
+    ///
+    /// 引数とエラーは`contains_group`と同じです。
+    pub fn filter_groups(
+        &self,
+        groups: Vec<Vec<OutPutTagValue>>,
+        tag_hierarchy: &[String],
+        tag_value_name: &TagValueName,
+    ) -> Result<Vec<Vec<OutPutTagValue>>, ValueConversionError> {
+        let mut filtered = Vec::new();
+        for group in groups {
+            if self.contains_group(&group, tag_hierarchy, tag_value_name)? {
+                filtered.push(group);
+            }
+        }
+        Ok(filtered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::reader::util::string_vec;
+    use crate::reader::xml_read_config::{XmlReadConfig, XmlTagReadConfig};
+    use crate::reader::xml_reader::read_xml;
+
+    #[test_log::test]
+    fn test_parse_xmltv_time() {
+        let date_time = parse_xmltv_time("20241123090000 +0900").unwrap();
+        assert_eq!(date_time.to_rfc3339(), "2024-11-23T09:00:00+09:00");
+        assert_eq!(format_xmltv_time(&date_time), "20241123090000 +0900");
+        assert_eq!(
+            parse_xmltv_time("202411230900 -0330").unwrap().to_rfc3339(),
+            "2024-11-23T09:00:00-03:30"
+        );
+        assert_eq!(
+            parse_xmltv_time("2024").unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+        // タイムゾーンとの間に空白がない場合
+        assert_eq!(parse_xmltv_time("20241123090000+0900").unwrap(), date_time);
+        assert_eq!(
+            parse_xmltv_time("20241123090000-0330")
+                .unwrap()
+                .to_rfc3339(),
+            "2024-11-23T09:00:00-03:30"
+        );
+        assert_eq!(
+            parse_xmltv_time("20241123000000Z").unwrap().to_rfc3339(),
+            "2024-11-23T00:00:00+00:00"
+        );
+        for invalid in [
+            "",
+            "2024112",
+            "20241131090000 +0900",
+            "20241123250000 +0900",
+            "20241123090000 JST",
+            "20241123090000 +09",
+            "20241123090000+0900 extra",
+            "+0900",
+            "2024-11-23",
+        ] {
+            assert!(parse_xmltv_time(invalid).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test_log::test]
+    fn test_filter_groups() {
+        let programme = string_vec(vec!["tv", "programme"]);
+        let start = TagValueName::new_attribute("start".to_string());
+        let mut config = XmlReadConfig::new(programme.clone());
+        config
+            .insert_xml_tag_read_config_to_hash_map(&XmlTagReadConfig::new(
+                programme.clone(),
+                HashSet::from_iter(vec![start.clone()]),
+            ))
+            .unwrap();
+        let mut reader = quick_xml::Reader::from_file("test_xml/sample1.xml").unwrap();
+        let groups = read_xml(&mut reader, &config).unwrap();
+        let count = groups.len();
+
+        // 同じ時刻をUTCで指定しても範囲に含まれる
+        let range = TimeRange::from_xmltv_times(Some("20241123000000 +0000"), None).unwrap();
+        let filtered = range
+            .filter_groups(groups.clone(), &programme, &start)
+            .unwrap();
+        assert_eq!(filtered.len(), count);
+
+        let range = TimeRange::from_xmltv_times(None, Some("20241123090000 +0900")).unwrap();
+        let filtered = range.filter_groups(groups, &programme, &start).unwrap();
+        assert!(filtered.is_empty());
+    }
+}